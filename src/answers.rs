@@ -0,0 +1,146 @@
+use crate::{InputError, InputOptions, InputSource, Part, Run, Solver};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const ANSWERS_FILE: &str = "answers.txt";
+
+/// Known-good answers, stored one `<day> <part> <answer>` per line.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Answers {
+    known: BTreeMap<(u16, Part), String>,
+}
+
+impl Answers {
+    pub fn path(opts: &InputOptions) -> PathBuf {
+        opts.input_dir().join(ANSWERS_FILE)
+    }
+
+    pub fn parse(s: &str) -> Result<Answers, InputError> {
+        let mut known = BTreeMap::new();
+        for (idx, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let malformed = || InputError::Malformed {
+                message: format!("answers line {}: expected `<day> <part> <answer>`", idx + 1),
+            };
+            let mut fields = line.splitn(3, ' ');
+            let day = fields
+                .next()
+                .and_then(|it| it.parse::<u16>().ok())
+                .ok_or_else(malformed)?;
+            let part = fields
+                .next()
+                .and_then(|it| it.parse::<Part>().ok())
+                .ok_or_else(malformed)?;
+            let answer = fields.next().ok_or_else(malformed)?.trim();
+            known.insert((day, part), answer.to_string());
+        }
+        Ok(Answers { known })
+    }
+
+    pub fn load(path: &Path) -> Result<Answers, InputError> {
+        match InputSource::File(path.into()).read() {
+            Ok(contents) => Self::parse(&contents),
+            Err(InputError::Missing { .. } | InputError::Empty { .. }) => Ok(Answers::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), InputError> {
+        let mut out = String::new();
+        for ((day, part), answer) in self.known.iter() {
+            writeln!(out, "{} {} {}", day, part, answer).unwrap();
+        }
+        std::fs::write(path, out).map_err(|source| InputError::Unwritable {
+            path: path.into(),
+            source,
+        })
+    }
+
+    pub fn get(&self, day: u16, part: Part) -> Option<&str> {
+        self.known.get(&(day, part)).map(|it| it.as_str())
+    }
+
+    pub fn set(&mut self, day: u16, part: Part, answer: String) {
+        self.known.insert((day, part), answer);
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Match,
+    Mismatch { expected: String },
+    Unrecorded,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Check {
+    pub day: u16,
+    pub part: Part,
+    pub actual: String,
+    pub outcome: Outcome,
+}
+
+pub fn record(
+    answers: &mut Answers,
+    solver: &dyn Solver,
+    part: Option<Part>,
+) -> Result<(), InputError> {
+    for answer in solver.solve(&solver.load()?, part)?.answers {
+        answers.set(solver.day(), answer.part, answer.value);
+    }
+    Ok(())
+}
+
+pub fn check(
+    answers: &Answers,
+    solver: &dyn Solver,
+    part: Option<Part>,
+) -> Result<Vec<Check>, InputError> {
+    Ok(compare(
+        answers,
+        solver.day(),
+        &solver.solve(&solver.load()?, part)?,
+    ))
+}
+
+/// Checks the answers of a finished run against the recorded ones.
+pub fn compare(answers: &Answers, day: u16, run: &Run) -> Vec<Check> {
+    run.answers
+        .iter()
+        .map(|answer| {
+            let outcome = match answers.get(day, answer.part) {
+                None => Outcome::Unrecorded,
+                Some(expected) if expected == answer.value => Outcome::Match,
+                Some(expected) => Outcome::Mismatch {
+                    expected: expected.to_string(),
+                },
+            };
+            Check {
+                day,
+                part: answer.part,
+                actual: answer.value.clone(),
+                outcome,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers =
+            Answers::parse("# day part answer\n10 1 370407\n\n24 2 24913111616151\n").unwrap();
+        assert_eq!(answers.get(10, Part::One), Some("370407"));
+        assert_eq!(answers.get(10, Part::Two), None);
+        assert_eq!(answers.get(24, Part::Two), Some("24913111616151"));
+        assert!(Answers::parse("10 3 1").is_err());
+        assert!(Answers::parse("10 1").is_err());
+    }
+}
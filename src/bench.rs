@@ -0,0 +1,110 @@
+use crate::{InputError, Part, Solver};
+use std::fmt::Write;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+}
+
+impl Stats {
+    fn of(samples: &mut [Duration]) -> Stats {
+        samples.sort_unstable();
+        Stats {
+            min: samples[0],
+            median: samples[(samples.len() - 1) / 2],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Bench {
+    pub day: u16,
+    pub runs: usize,
+    pub parse: Stats,
+    pub parts: Vec<(Part, Stats)>,
+}
+
+pub fn bench(solver: &dyn Solver, part: Option<Part>, runs: usize) -> Result<Bench, InputError> {
+    assert!(runs > 0);
+    let input = solver.load()?;
+    let mut parse_times = vec![];
+    let mut part_times: Vec<(Part, Vec<Duration>)> = vec![];
+    for _ in 0..runs {
+        let run = solver.solve(&input, part)?;
+        parse_times.push(run.parse_time);
+        for answer in run.answers {
+            match part_times.iter_mut().find(|(p, _)| *p == answer.part) {
+                Some((_, times)) => times.push(answer.elapsed),
+                None => part_times.push((answer.part, vec![answer.elapsed])),
+            }
+        }
+    }
+    Ok(Bench {
+        day: solver.day(),
+        runs,
+        parse: Stats::of(&mut parse_times),
+        parts: part_times
+            .into_iter()
+            .map(|(p, mut times)| (p, Stats::of(&mut times)))
+            .collect(),
+    })
+}
+
+pub(crate) fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs_f64();
+    if secs >= 1.0 {
+        format!("{:.3}s", secs)
+    } else if secs >= 1e-3 {
+        format!("{:.3}ms", secs * 1e3)
+    } else {
+        format!("{:.3}µs", secs * 1e6)
+    }
+}
+
+fn format_stats(stats: Option<&Stats>) -> String {
+    match stats {
+        Some(s) => format!("{} / {}", format_duration(s.min), format_duration(s.median)),
+        None => "-".to_string(),
+    }
+}
+
+pub fn table(benches: &[Bench]) -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "{:>4} {:>5} {:>23} {:>23} {:>23}",
+        "day", "runs", "parse (min / median)", "part 1 (min / median)", "part 2 (min / median)"
+    )
+    .unwrap();
+    for b in benches {
+        let part = |p: Part| b.parts.iter().find(|(it, _)| *it == p).map(|(_, s)| s);
+        writeln!(
+            out,
+            "{:>4} {:>5} {:>23} {:>23} {:>23}",
+            b.day,
+            b.runs,
+            format_stats(Some(&b.parse)),
+            format_stats(part(Part::One)),
+            format_stats(part(Part::Two)),
+        )
+        .unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let ms = Duration::from_millis;
+        let stats = Stats::of(&mut [ms(5), ms(1), ms(9), ms(3)]);
+        assert_eq!(stats.min, ms(1));
+        assert_eq!(stats.median, ms(3));
+        assert_eq!(format_duration(ms(1500)), "1.500s");
+        assert_eq!(format_duration(Duration::from_micros(2)), "2.000µs");
+    }
+}
@@ -0,0 +1,223 @@
+use adventofcode2021::answers::{self, Answers, Outcome};
+use adventofcode2021::days::{self, SOLUTIONS};
+use adventofcode2021::output::{self, Format};
+use adventofcode2021::report::{self, DayReport, Status};
+use adventofcode2021::{bench, examples, trace, InputError, InputOptions, Part, Solver};
+use std::time::Instant;
+
+const USAGE: &str = "usage: aoc [bench [--runs <n>] | record | check | examples] <day|all> [part] \
+                     [--example <name>] [--json] [--stream] [-v | -vv] [--input <file>] \
+                     [--input-dir <dir>]";
+const DEFAULT_BENCH_RUNS: usize = 5;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mode {
+    Run,
+    Bench,
+    Record,
+    Check,
+    Examples,
+}
+
+fn usage_error() -> InputError {
+    InputError::Usage {
+        message: USAGE.to_string(),
+    }
+}
+
+fn take_flag(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, InputError> {
+    match args.iter().position(|it| it == flag) {
+        Some(idx) if idx + 1 < args.len() => {
+            let value = args.remove(idx + 1);
+            args.remove(idx);
+            Ok(Some(value))
+        }
+        Some(_) => Err(usage_error()),
+        None => Ok(None),
+    }
+}
+
+fn selected_solvers(day: &str) -> Result<Vec<&'static dyn Solver>, InputError> {
+    if day == "all" {
+        return Ok(SOLUTIONS.to_vec());
+    }
+    let day = day.parse::<u16>().map_err(|_| usage_error())?;
+    let solver = days::find(day).ok_or_else(|| InputError::Usage {
+        message: format!("no solution for day {}", day),
+    })?;
+    Ok(vec![solver])
+}
+
+fn print_failure(report: &DayReport) {
+    match &report.status {
+        Status::Failed(err) => eprintln!("Day {}: error: {}", report.day, err),
+        Status::Panicked(message) => eprintln!("Day {}: panicked: {}", report.day, message),
+        Status::Solved(_) => {}
+    }
+}
+
+// Returns false if a check found a mismatch or a day failed.
+fn run() -> Result<bool, InputError> {
+    let (opts, mut args) = InputOptions::from_env()?;
+    let mode = match args.first().map(|it| it.as_str()) {
+        Some("bench") => Mode::Bench,
+        Some("record") => Mode::Record,
+        Some("check") => Mode::Check,
+        Some("examples") => Mode::Examples,
+        _ => Mode::Run,
+    };
+    if mode != Mode::Run {
+        args.remove(0);
+    }
+    let runs = match take_flag(&mut args, "--runs")? {
+        Some(runs) => runs
+            .parse::<usize>()
+            .ok()
+            .filter(|it| *it > 0)
+            .ok_or_else(usage_error)?,
+        None => DEFAULT_BENCH_RUNS,
+    };
+    let example = take_flag(&mut args, "--example")?;
+    let format = Format::from_args(&mut args);
+    trace::set_level(trace::Level::from_args(&mut args));
+    let stream = args.iter().any(|it| it == "--stream");
+    args.retain(|it| it != "--stream");
+    if args.is_empty() || args.len() > 2 {
+        return Err(usage_error());
+    }
+    let solvers = selected_solvers(&args[0])?;
+    let part = match args.get(1) {
+        Some(part) => Some(part.parse::<Part>()?),
+        None => None,
+    };
+
+    match mode {
+        Mode::Run if solvers.len() > 1 && example.is_none() && !stream => {
+            let start = Instant::now();
+            let reports = report::run_all(&solvers, part);
+            match format {
+                Format::Text => print!("{}", report::summary(&reports, start.elapsed())),
+                Format::Json => {
+                    for report in &reports {
+                        match (&report.status, report.fingerprint) {
+                            (Status::Solved(run), Some(fingerprint)) => {
+                                for line in output::lines(report.day, run, fingerprint, format) {
+                                    println!("{}", line);
+                                }
+                            }
+                            _ => print_failure(report),
+                        }
+                    }
+                }
+            }
+            if !reports.iter().all(DayReport::is_ok) {
+                return Ok(false);
+            }
+        }
+        Mode::Run => {
+            let print_headers = solvers.len() > 1 && format == Format::Text;
+            for solver in solvers {
+                if print_headers {
+                    println!("Day {}", solver.day());
+                }
+                match &example {
+                    Some(name) => {
+                        let example = examples::load(&examples::dir(&opts), solver.day(), name)?;
+                        adventofcode2021::run_on(solver, &example.input, part, format)?;
+                    }
+                    None if stream => adventofcode2021::run_streaming(
+                        solver,
+                        &opts.source(solver.day()),
+                        part,
+                        format,
+                    )?,
+                    None => adventofcode2021::run_on(solver, &solver.load()?, part, format)?,
+                }
+            }
+        }
+        Mode::Examples => {
+            let mut mismatches = 0;
+            for solver in solvers {
+                for example in examples::for_day(&examples::dir(&opts), solver.day())? {
+                    let parts: Vec<Part> = example
+                        .expected
+                        .keys()
+                        .copied()
+                        .filter(|it| it.wanted(part))
+                        .collect();
+                    for p in parts {
+                        let run = solver.solve(&example.input, Some(p))?;
+                        let actual = &run.answers[0].value;
+                        let expected = &example.expected[&p];
+                        let status = if actual == expected {
+                            "ok".to_string()
+                        } else {
+                            mismatches += 1;
+                            format!("MISMATCH (expected {})", expected)
+                        };
+                        println!(
+                            "Day {} example {} part {}: {} {}",
+                            example.day, example.name, p, actual, status
+                        );
+                    }
+                }
+            }
+            if mismatches > 0 {
+                println!("{} example answer(s) wrong", mismatches);
+                return Ok(false);
+            }
+        }
+        Mode::Bench => {
+            let mut benches = vec![];
+            for solver in solvers {
+                benches.push(bench::bench(solver, part, runs)?);
+            }
+            print!("{}", bench::table(&benches));
+        }
+        Mode::Record => {
+            let path = Answers::path(&opts);
+            let mut known = Answers::load(&path)?;
+            for solver in solvers {
+                answers::record(&mut known, solver, part)?;
+            }
+            known.save(&path)?;
+            println!("Recorded answers in {}", path.display());
+        }
+        Mode::Check => {
+            let known = Answers::load(&Answers::path(&opts))?;
+            let mut mismatches = 0;
+            for report in report::run_all(&solvers, part) {
+                let Status::Solved(run) = &report.status else {
+                    print_failure(&report);
+                    mismatches += 1;
+                    continue;
+                };
+                for check in answers::compare(&known, report.day, run) {
+                    let status = match &check.outcome {
+                        Outcome::Match => "ok".to_string(),
+                        Outcome::Unrecorded => "no recorded answer".to_string(),
+                        Outcome::Mismatch { expected } => {
+                            mismatches += 1;
+                            format!("MISMATCH (expected {})", expected)
+                        }
+                    };
+                    println!(
+                        "Day {} part {}: {} {}",
+                        check.day, check.part, check.actual, status
+                    );
+                }
+            }
+            if mismatches > 0 {
+                println!("{} answer(s) changed or failed", mismatches);
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
+
+fn main() {
+    if !adventofcode2021::exit_on_error(run()) {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,209 @@
+use adventofcode2021::brackets::{Checker, Delimiters, ParseResult, Score, Scoring, Syntax};
+use adventofcode2021::{InputError, InputSource, Line};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const USAGE: &str = "\
+usage: brackets [--delimiters <file>] [--syntax c|<file>]
+                [--scoring <file> | --all | --fix | --tree] <file|->";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mode {
+    FirstError,
+    AllErrors,
+    Fix,
+    Tree,
+}
+
+fn usage_error() -> InputError {
+    InputError::Usage {
+        message: USAGE.to_string(),
+    }
+}
+
+fn source(arg: &str) -> InputSource {
+    if arg == "-" {
+        InputSource::Stdin
+    } else {
+        InputSource::File(PathBuf::from(arg))
+    }
+}
+
+// Removes `flag` and the argument after it, returning the argument.
+fn option(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, InputError> {
+    match args.iter().position(|it| it == flag) {
+        Some(idx) if idx + 1 < args.len() => {
+            let value = args.remove(idx + 1);
+            args.remove(idx);
+            Ok(Some(value))
+        }
+        Some(_) => Err(usage_error()),
+        None => Ok(None),
+    }
+}
+
+// Checks source code as it is read, explaining its first mismatch. Only the lines where
+// delimiters are still open are kept, which is all the explanation needs besides the last line.
+fn check_streamed(checker: &Checker, path: &str) -> Result<ParseResult, InputError> {
+    let mut validator = checker.validator();
+    let mut kept: HashMap<usize, String> = HashMap::new();
+    for line in source(path).lines()? {
+        let Line { number, text } = line?;
+        validator.push(&text)?;
+        kept.insert(number, text);
+        // Once corrupted, every push returns the mismatch.
+        if validator.push("\n")?.is_some() {
+            break;
+        }
+        let mut open: Vec<usize> = validator.open().map(|it| it.line).collect();
+        open.push(number);
+        kept.retain(|number, _| open.contains(number));
+    }
+    let result = validator.finish()?;
+    if let ParseResult::Corrupted(mismatch) = &result {
+        let line = |number| kept.get(&number).map_or("", String::as_str);
+        println!("{}", checker.explain_lines(mismatch, line));
+    }
+    Ok(result)
+}
+
+// Explains every corrupted line of a file, or with `--all` every error in it, returning false if
+// there were any. With `--fix`, prints the file with corrupted lines repaired instead, and with
+// `--tree` the nesting of each line that is not corrupted. With `--syntax`, the file is source
+// code and is checked as a whole rather than line by line, streaming it unless another mode needs
+// all of it at once, and with `--scoring` the scores of the corrupted and incomplete lines are
+// printed too.
+fn run() -> Result<bool, InputError> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let mut checker = match option(&mut args, "--delimiters")? {
+        Some(path) => Checker::new(Delimiters::parse(&source(&path).read()?)?),
+        None => Checker::standard(),
+    };
+    match option(&mut args, "--syntax")?.as_deref() {
+        Some("c") => checker = checker.with_syntax(Syntax::c()),
+        Some(path) => checker = checker.with_syntax(Syntax::parse(&source(path).read()?)?),
+        None => {}
+    }
+    let scored = match option(&mut args, "--scoring")? {
+        Some(path) => {
+            let scoring = Scoring::parse(&source(&path).read()?, checker.delimiters())?;
+            checker = checker.with_scoring(scoring);
+            true
+        }
+        None => false,
+    };
+    let flags = [
+        ("--all", Mode::AllErrors),
+        ("--fix", Mode::Fix),
+        ("--tree", Mode::Tree),
+    ];
+    let mut modes = flags
+        .iter()
+        .filter(|(flag, _)| args.iter().any(|it| it == flag));
+    let mode = modes.next().map_or(Mode::FirstError, |it| it.1);
+    // Only the first error on a line is scored, so scores go with none of the other modes.
+    if modes.next().is_some() || scored && mode != Mode::FirstError {
+        return Err(usage_error());
+    }
+    args.retain(|it| flags.iter().all(|(flag, _)| it != flag));
+    let [path] = &args[..] else {
+        return Err(usage_error());
+    };
+    let mut results = vec![];
+    // Lines are checked as they are read; a source file is checked as a whole.
+    let (unit, units): (_, Box<dyn Iterator<Item = Result<Line, InputError>>>) =
+        if checker.syntax().is_none() {
+            ("line", Box::new(source(path).lines()?))
+        } else if mode == Mode::FirstError {
+            results.push(check_streamed(&checker, path)?);
+            ("file", Box::new(std::iter::empty()))
+        } else {
+            // Not `read`, which trims the file and would throw line numbers off.
+            let lines: Vec<Line> = source(path).lines()?.collect::<Result<_, _>>()?;
+            let text = lines
+                .iter()
+                .map(|it| it.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            (
+                "file",
+                Box::new(std::iter::once(Ok(Line { number: 1, text }))),
+            )
+        };
+    let (mut corrupted, mut incomplete, mut edits) = (0, 0, 0);
+    for line in units {
+        let line = line?;
+        let field = line.field();
+        if mode == Mode::Fix {
+            let repair = checker.repair(&field)?;
+            if !repair.edits.is_empty() {
+                corrupted += 1;
+                edits += repair.edits.len();
+            }
+            println!("{}", repair.repaired);
+            continue;
+        }
+        if mode == Mode::Tree {
+            match checker.tree(&field)? {
+                Some(tree) if !tree.groups.is_empty() => {
+                    if tree.iter().any(|it| it.close.is_none()) {
+                        incomplete += 1;
+                    }
+                    println!("{} {}, max depth {}:", unit, line.number, tree.max_depth());
+                    print!("{}", checker.render_tree(&tree));
+                }
+                Some(_) => {}
+                None => corrupted += 1,
+            }
+            continue;
+        }
+        if mode == Mode::AllErrors {
+            let recovered = checker.check_all(&field)?;
+            for (mismatch, _) in &recovered.errors {
+                println!("{}", checker.explain_mismatch(&field, mismatch));
+            }
+            if !recovered.errors.is_empty() {
+                corrupted += 1;
+            } else if !recovered.completion.is_empty() {
+                incomplete += 1;
+            }
+            continue;
+        }
+        let result = checker.check(&field)?;
+        if let Some(explanation) = checker.explain(&field, &result) {
+            println!("{}", explanation);
+        }
+        results.push(result);
+    }
+    for result in &results {
+        match result {
+            ParseResult::Corrupted(_) => corrupted += 1,
+            ParseResult::Incomplete { .. } => incomplete += 1,
+            ParseResult::Ok => {}
+        }
+    }
+    if mode == Mode::Fix {
+        eprintln!("{} {}(s) repaired with {} edit(s)", corrupted, unit, edits);
+        return Ok(true);
+    }
+    println!(
+        "{} corrupted {}(s), {} incomplete",
+        corrupted, unit, incomplete
+    );
+    if scored {
+        let (corruption, completion) = checker.scoring().aggregate(&checker, &results);
+        let show = |score: Option<Score>| score.map_or("none".to_string(), |it| it.to_string());
+        println!(
+            "corruption score {}, completion score {}",
+            show(corruption),
+            show(completion)
+        );
+    }
+    Ok(corrupted == 0)
+}
+
+fn main() {
+    if !adventofcode2021::exit_on_error(run()) {
+        std::process::exit(1);
+    }
+}
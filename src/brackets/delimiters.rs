@@ -0,0 +1,104 @@
+use crate::parse::{self, Field, ParseError};
+
+/// The table behind the day 10 puzzle.
+const STANDARD: &str = "\
+( ) 3 1
+[ ] 57 2
+{ } 1197 3
+< > 25137 4
+";
+
+/// An open/close pair, with the score for finding its closer out of place and the score for
+/// each closer a completion has to add.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Delimiter {
+    pub open: String,
+    pub close: String,
+    pub corruption_score: u32,
+    pub completion_score: u64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Token {
+    Open(usize),
+    Close(usize),
+}
+
+/// The pairs a checker knows about. Pairs are referred to by their index in the table; tokens may
+/// be several characters long, and the longest token that matches is used.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Delimiters {
+    pairs: Vec<Delimiter>,
+}
+
+impl Delimiters {
+    pub fn standard() -> Delimiters {
+        Self::parse(STANDARD).unwrap()
+    }
+
+    /// Parses one pair per line as `<open> <close> <corruption score> <completion score>`, such
+    /// as `begin end 5 2`.
+    pub fn parse(input: &str) -> Result<Delimiters, ParseError> {
+        let mut pairs: Vec<Delimiter> = vec![];
+        let mut tokens: Vec<Field> = vec![];
+        for (line, fields) in parse::config_lines(input) {
+            if fields.len() != 4 {
+                return Err(
+                    line.error("expected <open> <close> <corruption score> <completion score>")
+                );
+            }
+            for token in &fields[..2] {
+                if tokens.iter().any(|it| it.text == token.text) {
+                    return Err(token.error(format!("{:?} is already a delimiter", token.text)));
+                }
+                tokens.push(*token);
+            }
+            pairs.push(Delimiter {
+                open: fields[0].text.to_string(),
+                close: fields[1].text.to_string(),
+                corruption_score: fields[2].parse()?,
+                completion_score: fields[3].parse()?,
+            });
+        }
+        if pairs.is_empty() {
+            return Err(Field::new(1, input).error("no delimiters defined"));
+        }
+        Ok(Delimiters { pairs })
+    }
+
+    pub fn get(&self, id: usize) -> &Delimiter {
+        &self.pairs[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    // Whether `s` is the start of a longer token.
+    pub(super) fn is_prefix(&self, s: &str) -> bool {
+        self.pairs
+            .iter()
+            .flat_map(|it| [&it.open, &it.close])
+            .any(|it| it.len() > s.len() && it.starts_with(s))
+    }
+
+    /// The longest token at the start of `s`, with its length in bytes.
+    pub fn token_at(&self, s: &str) -> Option<(Token, usize)> {
+        let mut best: Option<(Token, usize)> = None;
+        for (id, pair) in self.pairs.iter().enumerate() {
+            for (token, text) in [
+                (Token::Open(id), &pair.open),
+                (Token::Close(id), &pair.close),
+            ] {
+                if s.starts_with(text.as_str()) && best.is_none_or(|(_, len)| text.len() > len) {
+                    best = Some((token, text.len()));
+                }
+            }
+        }
+        best
+    }
+}
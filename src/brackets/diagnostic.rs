@@ -0,0 +1,190 @@
+use super::{Checker, Mismatch, ParseResult, Span};
+use crate::parse::Field;
+use std::fmt::Write;
+
+impl Checker {
+    /// Explains a corrupted line in the style of rustc, pointing at the illegal closer and at the
+    /// delimiter it should have closed. Other results need no explanation.
+    pub fn explain(&self, line: &Field, result: &ParseResult) -> Option<String> {
+        match result {
+            ParseResult::Corrupted(mismatch) => Some(self.explain_mismatch(line, mismatch)),
+            _ => None,
+        }
+    }
+
+    pub fn explain_mismatch(&self, line: &Field, mismatch: &Mismatch) -> String {
+        self.render_mismatch(mismatch, |span| source_line(line, span))
+    }
+
+    /// Explains a mismatch in text that was never held in one piece, such as text checked by a
+    /// `Validator`, given the lines the mismatch and its opener are on.
+    pub fn explain_lines<'a>(
+        &self,
+        mismatch: &Mismatch,
+        line: impl Fn(usize) -> &'a str,
+    ) -> String {
+        self.render_mismatch(mismatch, |span| (line(span.line).trim_end(), 1))
+    }
+
+    // Renders a mismatch given the source line each span is on and the column that line starts at.
+    fn render_mismatch<'a>(
+        &self,
+        mismatch: &Mismatch,
+        source_line: impl Fn(&Span) -> (&'a str, usize),
+    ) -> String {
+        let Mismatch {
+            illegal,
+            at,
+            expected,
+            opener,
+        } = mismatch;
+        let found = &self.delimiters.get(*illegal).close;
+        let gutter = " ".repeat(at.line.to_string().len());
+        // Columns within the printed line, and widths in characters.
+        let indent = |span: &Span| span.column - source_line(span).1;
+        let width = |span: &Span| {
+            let text = source_line(span).0;
+            let start = text
+                .char_indices()
+                .nth(indent(span))
+                .map_or(text.len(), |it| it.0);
+            text[start..(start + span.len).min(text.len())]
+                .chars()
+                .count()
+        };
+        let marks = |spans: &[(&Span, char)]| {
+            let mut marks = String::new();
+            for (span, mark) in spans {
+                marks.push_str(&" ".repeat(indent(span) - marks.chars().count()));
+                marks.push_str(&mark.to_string().repeat(width(span)));
+            }
+            marks
+        };
+
+        let mut out = String::new();
+        let label = match expected {
+            Some(expected) => {
+                let expected = &self.delimiters.get(*expected).close;
+                writeln!(out, "error: expected `{}`, found `{}`", expected, found).unwrap();
+                format!("expected `{}`", expected)
+            }
+            None => {
+                writeln!(out, "error: unexpected `{}`", found).unwrap();
+                "nothing is open here".to_string()
+            }
+        };
+        let opened = || {
+            let open = &self.delimiters.get(expected.unwrap()).open;
+            format!("`{}` opened here", open)
+        };
+        writeln!(out, "{}--> {}:{}", gutter, at.line, at.column).unwrap();
+        writeln!(out, "{} |", gutter).unwrap();
+        let number = |n: usize| format!("{:>1$}", n, gutter.len());
+        match opener {
+            Some(opener) if opener.line != at.line => {
+                writeln!(out, "{} | {}", number(opener.line), source_line(opener).0).unwrap();
+                let dashes = marks(&[(opener, '-')]);
+                writeln!(out, "{} | {} {}", gutter, dashes, opened()).unwrap();
+                if at.line > opener.line + 1 {
+                    writeln!(out, "...").unwrap();
+                }
+                writeln!(out, "{} | {}", at.line, source_line(at).0).unwrap();
+                writeln!(out, "{} | {} {}", gutter, marks(&[(at, '^')]), label).unwrap();
+            }
+            _ => {
+                writeln!(out, "{} | {}", at.line, source_line(at).0).unwrap();
+                let mut spans: Vec<(&Span, char)> = opener.iter().map(|it| (it, '-')).collect();
+                spans.push((at, '^'));
+                writeln!(out, "{} | {} {}", gutter, marks(&spans), label).unwrap();
+                if let Some(opener) = opener {
+                    let pad = " ".repeat(indent(opener));
+                    writeln!(out, "{} | {}|", gutter, pad).unwrap();
+                    writeln!(out, "{} | {}{}", gutter, pad, opened()).unwrap();
+                }
+            }
+        }
+        out
+    }
+}
+
+// The line of a possibly multi-line field that `span` is on, and the column it starts at.
+fn source_line<'a>(line: &Field<'a>, span: &Span) -> (&'a str, usize) {
+    let start = line.text[..span.offset]
+        .rfind('\n')
+        .map_or(0, |idx| idx + 1);
+    let end = line.text[span.offset..]
+        .find('\n')
+        .map_or(line.text.len(), |idx| span.offset + idx);
+    let column = if start == 0 { line.column } else { 1 };
+    (line.text[start..end].trim_end(), column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brackets::Syntax;
+
+    #[test]
+    fn test_explain() {
+        let checker = Checker::standard();
+        let line = Field::new(3, "{([(<{}[<>[]}>{[]{[(<()>");
+        let result = checker.check(&line).unwrap();
+        let ParseResult::Corrupted(Mismatch { at, opener, .. }) = &result else {
+            panic!("expected a corrupted line");
+        };
+        assert_eq!((at.offset, at.column), (12, 13));
+        assert_eq!(opener.map(|it| it.column), Some(8));
+        assert_eq!(
+            checker.explain(&line, &result).unwrap(),
+            "\
+error: expected `]`, found `}`
+ --> 3:13
+  |
+3 | {([(<{}[<>[]}>{[]{[(<()>
+  |        -    ^ expected `]`
+  |        |
+  |        `[` opened here
+"
+        );
+        let line = Field::new(12, "<>)");
+        let result = checker.check(&line).unwrap();
+        assert_eq!(
+            checker.explain(&line, &result).unwrap(),
+            "\
+error: unexpected `)`
+  --> 12:3
+   |
+12 | <>)
+   |   ^ nothing is open here
+"
+        );
+        assert_eq!(checker.explain(&line, &ParseResult::Ok), None);
+
+        let checker = Checker::standard().with_syntax(Syntax::c());
+        let source = Field::new(1, "fn f() {\n    g(1; // }\n\n}\n");
+        let result = checker.check(&source).unwrap();
+        assert_eq!(
+            checker.explain(&source, &result).unwrap(),
+            "\
+error: expected `)`, found `}`
+ --> 4:1
+  |
+2 |     g(1; // }
+  |      - `(` opened here
+...
+4 | }
+  | ^ expected `)`
+"
+        );
+        // Given only the lines it is on, a mismatch is explained just the same.
+        let ParseResult::Corrupted(mismatch) = &result else {
+            panic!("expected corrupted source");
+        };
+        let lines: Vec<&str> = source.text.lines().collect();
+        let explained = checker.explain_lines(mismatch, |number| match number {
+            2 | 4 => lines[number - 1],
+            _ => panic!("line {} is not needed", number),
+        });
+        assert_eq!(explained, checker.explain_mismatch(&source, mismatch));
+    }
+}
@@ -0,0 +1,469 @@
+use super::{Delimiters, Span, Token};
+use crate::parse::{self, Field, ParseError};
+
+/// The parts of source code that can hold delimiters without them counting: strings, character
+/// literals and comments. Block comments do not nest, and strings may run over several lines.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Syntax {
+    pub strings: Vec<char>,
+    /// Only a quote around a single character or an escape sequence starts a literal, so a
+    /// lifetime such as `'a` is skipped as an ordinary character.
+    pub char_literal: Option<char>,
+    pub escape: Option<char>,
+    pub line_comments: Vec<String>,
+    pub block_comments: Vec<(String, String)>,
+}
+
+/// A string or comment that carries on past the end of the text read so far.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum Unfinished {
+    LineComment,
+    /// The index of the block comment's delimiters.
+    BlockComment(usize),
+    /// Whether the last character read was the escape character.
+    String {
+        quote: char,
+        escaped: bool,
+    },
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl Syntax {
+    /// C, Rust, Java and the like, though not Rust's raw strings.
+    pub fn c() -> Syntax {
+        Syntax {
+            strings: vec!['"'],
+            char_literal: Some('\''),
+            escape: Some('\\'),
+            line_comments: vec!["//".to_string()],
+            block_comments: vec![("/*".to_string(), "*/".to_string())],
+        }
+    }
+
+    /// Parses one rule per line: `string <quote>`, `char <quote>`, `escape <char>`,
+    /// `line-comment <start>` or `block-comment <start> <end>`.
+    pub fn parse(input: &str) -> Result<Syntax, ParseError> {
+        let single = |field: &Field| {
+            let mut chars = field.text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(field.error("expected a single character")),
+            }
+        };
+        let mut syntax = Syntax::default();
+        for (line, fields) in parse::config_lines(input) {
+            let (rule, args) = (fields[0], &fields[1..]);
+            let expected = if rule.text == "block-comment" { 2 } else { 1 };
+            if args.len() != expected {
+                let usage = match rule.text {
+                    "block-comment" => "expected block-comment <start> <end>",
+                    _ => "expected <rule> <text>",
+                };
+                return Err(line.error(usage));
+            }
+            match rule.text {
+                "string" => syntax.strings.push(single(&args[0])?),
+                "char" => syntax.char_literal = Some(single(&args[0])?),
+                "escape" => syntax.escape = Some(single(&args[0])?),
+                "line-comment" => syntax.line_comments.push(args[0].text.to_string()),
+                "block-comment" => syntax
+                    .block_comments
+                    .push((args[0].text.to_string(), args[1].text.to_string())),
+                _ => return Err(rule.error(format!("unknown rule {:?}", rule.text))),
+            }
+        }
+        Ok(syntax)
+    }
+
+    /// The string or comment starting at `s`, if there is one, with the length of its opening.
+    fn start(&self, s: &str) -> Option<(usize, Unfinished)> {
+        if let Some(start) = self
+            .line_comments
+            .iter()
+            .find(|it| s.starts_with(it.as_str()))
+        {
+            return Some((start.len(), Unfinished::LineComment));
+        }
+        for (idx, (start, _)) in self.block_comments.iter().enumerate() {
+            if s.starts_with(start.as_str()) {
+                return Some((start.len(), Unfinished::BlockComment(idx)));
+            }
+        }
+        let quote = s.chars().next()?;
+        let escaped = false;
+        self.strings
+            .contains(&quote)
+            .then_some((quote.len_utf8(), Unfinished::String { quote, escaped }))
+    }
+
+    /// The length of `s` up to the end of the string or comment it continues, or if it does not
+    /// end there, how much of `s` it certainly takes up.
+    fn finish(&self, unfinished: &mut Unfinished, s: &str) -> Result<usize, usize> {
+        match unfinished {
+            Unfinished::LineComment => s.find('\n').ok_or(s.len()),
+            Unfinished::BlockComment(idx) => {
+                let end = self.block_comments[*idx].1.as_str();
+                match s.find(end) {
+                    Some(idx) => Ok(idx + end.len()),
+                    None => {
+                        // Keeps back what could be the start of the end.
+                        let mut len = s.len().saturating_sub(end.len() - 1);
+                        while !s.is_char_boundary(len) {
+                            len -= 1;
+                        }
+                        Err(len)
+                    }
+                }
+            }
+            Unfinished::String { quote, escaped } => {
+                for (idx, c) in s.char_indices() {
+                    if *escaped {
+                        *escaped = false;
+                    } else if Some(c) == self.escape {
+                        *escaped = true;
+                    } else if c == *quote {
+                        return Ok(idx + c.len_utf8());
+                    }
+                }
+                Err(s.len())
+            }
+        }
+    }
+
+    /// The length in bytes of the character literal at the start of `s`, if there is one.
+    fn char_literal(&self, s: &str) -> Option<usize> {
+        let quote = s.chars().next()?;
+        if Some(quote) == self.char_literal {
+            let rest = &s[quote.len_utf8()..];
+            let mut chars = rest.chars();
+            let len = match chars.next()? {
+                '\n' => return None,
+                escape if Some(escape) == self.escape => {
+                    let body = &rest[escape.len_utf8()..];
+                    let len = escape_len(body).ok()?;
+                    if !body[len..].starts_with(quote) {
+                        return None;
+                    }
+                    escape.len_utf8() + len
+                }
+                c if c == quote => return None,
+                c if chars.next() == Some(quote) => c.len_utf8(),
+                _ => return None,
+            };
+            return Some(2 * quote.len_utf8() + len);
+        }
+        None
+    }
+
+    // Whether more text after `s` could make it the start of a comment or character literal.
+    fn is_prefix(&self, s: &str) -> bool {
+        let mut starts = self
+            .line_comments
+            .iter()
+            .chain(self.block_comments.iter().map(|it| &it.0));
+        if starts.any(|it| it.len() > s.len() && it.starts_with(s)) {
+            return true;
+        }
+        match self.char_literal {
+            Some(quote) if s.starts_with(quote) && self.char_literal(s).is_none() => {
+                let rest = &s[quote.len_utf8()..];
+                let mut chars = rest.chars();
+                match chars.next() {
+                    None => true,
+                    Some(escape) if Some(escape) == self.escape => {
+                        let body = &rest[escape.len_utf8()..];
+                        let (Ok(len) | Err(len)) = escape_len(body);
+                        len == body.len()
+                    }
+                    Some(c) => c != quote && c != '\n' && chars.next().is_none(),
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+// The length of the escape sequence at the start of `s`, after its escape character: the escaped
+// character, then if it is a letter or digit any more of them, and a braced group such as the
+// one in `\u{7FFF}`. If there is none, how far into `s` that became clear.
+fn escape_len(s: &str) -> Result<usize, usize> {
+    let Some(escaped) = s.chars().next().filter(|it| *it != '\n') else {
+        return Err(0);
+    };
+    let mut len = escaped.len_utf8();
+    if escaped.is_ascii_alphanumeric() {
+        let alphanumeric = |s: &str| {
+            s.find(|it: char| !it.is_ascii_alphanumeric())
+                .unwrap_or(s.len())
+        };
+        len += alphanumeric(&s[len..]);
+        if s[len..].starts_with('{') {
+            let group = 1 + alphanumeric(&s[len + 1..]);
+            if !s[len + group..].starts_with('}') {
+                return Err(len + group);
+            }
+            len += group + 1;
+        }
+    }
+    Ok(len)
+}
+
+/// The delimiters in a line, skipping whitespace between them. Given a syntax, anything else is
+/// skipped too, and a token made of letters only counts as a whole word, so `end` is not found in
+/// `append`.
+pub struct Tokens<'c, 'a> {
+    delimiters: &'c Delimiters,
+    syntax: Option<&'c Syntax>,
+    line: Field<'a>,
+    offset: usize,
+    // The text may hold several lines: the number of the one `offset` is on, and where it starts.
+    line_number: usize,
+    line_start: usize,
+    // Whether more text may follow, so anything it could still change is left unread.
+    partial: bool,
+    // A string or comment the text starts inside of, or runs on past its end.
+    unfinished: Option<Unfinished>,
+}
+
+impl<'c, 'a> Tokens<'c, 'a> {
+    pub(super) fn new(
+        delimiters: &'c Delimiters,
+        syntax: Option<&'c Syntax>,
+        line: Field<'a>,
+    ) -> Tokens<'c, 'a> {
+        Tokens {
+            delimiters,
+            syntax,
+            line,
+            offset: 0,
+            line_number: line.line,
+            line_start: 0,
+            partial: false,
+            unfinished: None,
+        }
+    }
+
+    /// Stops before the first token, string, comment or word that more text could change.
+    pub(super) fn partial(mut self) -> Tokens<'c, 'a> {
+        self.partial = true;
+        self
+    }
+
+    /// Starts inside a string or comment left unfinished by the text before.
+    pub(super) fn continuing(mut self, unfinished: Option<Unfinished>) -> Tokens<'c, 'a> {
+        self.unfinished = unfinished;
+        self
+    }
+
+    /// The string or comment that partial text stopped inside of, if any.
+    pub(super) fn unfinished(&self) -> Option<Unfinished> {
+        self.unfinished.clone()
+    }
+
+    /// The offset of the first unread byte, and its line and column.
+    pub(super) fn position(&self) -> (usize, usize, usize) {
+        (self.offset, self.line_number, self.column())
+    }
+
+    fn advance(&mut self, len: usize) {
+        let skipped = &self.line.text[self.offset..self.offset + len];
+        if let Some(idx) = skipped.rfind('\n') {
+            self.line_number += skipped.matches('\n').count();
+            self.line_start = self.offset + idx + 1;
+        }
+        self.offset += len;
+    }
+
+    fn column(&self) -> usize {
+        let first = if self.line_start == 0 {
+            self.line.column
+        } else {
+            1
+        };
+        first + self.line.text[self.line_start..self.offset].chars().count()
+    }
+
+    fn on_boundary(&self, len: usize) -> bool {
+        let token = &self.line.text[self.offset..self.offset + len];
+        let next = self.line.text[self.offset + len..].chars().next();
+        !(token.ends_with(is_word) && next.is_some_and(is_word))
+    }
+}
+
+impl Iterator for Tokens<'_, '_> {
+    type Item = Result<(Token, Span), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(mut unfinished) = self.unfinished.take() {
+                let syntax = self.syntax?;
+                match syntax.finish(&mut unfinished, &self.line.text[self.offset..]) {
+                    Ok(len) => self.advance(len),
+                    // Read what is certainly part of it, and wait for more text.
+                    Err(len) if self.partial => {
+                        self.advance(len);
+                        self.unfinished = Some(unfinished);
+                        return None;
+                    }
+                    // Anything left unterminated runs to the end of the text.
+                    Err(_) => self.advance(self.line.text.len() - self.offset),
+                }
+                continue;
+            }
+            let rest = &self.line.text[self.offset..];
+            self.advance(rest.len() - rest.trim_start().len());
+            let rest = &self.line.text[self.offset..];
+            let c = rest.chars().next()?;
+            let is_prefix =
+                self.delimiters.is_prefix(rest) || self.syntax.is_some_and(|it| it.is_prefix(rest));
+            if self.partial && is_prefix {
+                return None;
+            }
+            let Some(syntax) = self.syntax else {
+                break;
+            };
+            // Whatever runs to the end of the text might carry on past it.
+            let unfinished = |len: usize| self.partial && len == rest.len();
+            if let Some((len, started)) = syntax.start(rest) {
+                self.advance(len);
+                self.unfinished = Some(started);
+                continue;
+            }
+            if let Some(len) = syntax.char_literal(rest) {
+                if unfinished(len) {
+                    return None;
+                }
+                self.advance(len);
+                continue;
+            }
+            match self.delimiters.token_at(rest) {
+                Some((_, len)) if unfinished(len) && rest.ends_with(is_word) => return None,
+                Some((_, len)) if self.on_boundary(len) => break,
+                // Skipping a whole word means a token is only ever looked for at the start of one.
+                _ if is_word(c) => {
+                    let len = rest.find(|it| !is_word(it)).unwrap_or(rest.len());
+                    if unfinished(len) {
+                        return None;
+                    }
+                    self.advance(len);
+                }
+                _ => self.advance(c.len_utf8()),
+            }
+        }
+        let rest = &self.line.text[self.offset..];
+        let column = self.column();
+        let Some((token, len)) = self.delimiters.token_at(rest) else {
+            let c = rest.chars().next()?;
+            self.offset = self.line.text.len();
+            return Some(Err(ParseError {
+                line: self.line_number,
+                column,
+                message: format!("unexpected character {:?}", c),
+            }));
+        };
+        let span = Span {
+            offset: self.offset,
+            len,
+            line: self.line_number,
+            column,
+        };
+        self.advance(len);
+        Some(Ok((token, span)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brackets::{Checker, Mismatch, ParseResult};
+
+    const SOURCE: &str = r#"fn main() {
+    let s = "}(";  // a stray ]
+    let c = '{';
+    /* [ ( */
+    let v: Vec<&'a str> = vec![s, '\u{7D}'.to_string()];
+    if (c == ']' { }
+}
+"#;
+
+    #[test]
+    fn test_syntax() {
+        let checker = Checker::standard().with_syntax(Syntax::c());
+        let source = Field::new(1, SOURCE);
+        let ParseResult::Corrupted(Mismatch {
+            illegal,
+            at,
+            expected,
+            opener,
+        }) = checker.check(&source).unwrap()
+        else {
+            panic!("expected corrupted source");
+        };
+        // The generics in `Vec<&'a str>` are delimiters too, and the `(` after `if` is unclosed.
+        assert_eq!((illegal, expected), (2, Some(0)));
+        assert_eq!((at.line, at.column), (7, 1));
+        assert_eq!(&SOURCE[at.offset..at.offset + at.len], "}");
+        let opener = opener.unwrap();
+        assert_eq!((opener.line, opener.column), (6, 8));
+
+        let check = |s| checker.check(&Field::new(1, s)).unwrap();
+        assert_eq!(check("f(x, \"a)\\\"]\") // )"), ParseResult::Ok);
+        assert_eq!(
+            check("/* ( */ [ /* unterminated ]"),
+            ParseResult::Incomplete { stack: vec![1] }
+        );
+
+        let words = Checker::new(Delimiters::parse("begin end 10 1\n( ) 3 2").unwrap())
+            .with_syntax(Syntax::default());
+        let check = |s| words.check(&Field::new(1, s)).unwrap();
+        assert_eq!(check("begin append(x); end"), ParseResult::Ok);
+        assert_eq!(check("begin x.end_of() end"), ParseResult::Ok);
+        assert!(matches!(check("beginning end"), ParseResult::Corrupted(_)));
+
+        // A literal is one escape sequence between quotes, so `'\') '` does not hide its `)`.
+        let c_syntax = Checker::standard().with_syntax(Syntax::c());
+        let check = |s| c_syntax.check(&Field::new(1, s)).unwrap();
+        assert_eq!(check("('\\'') ('\\\\')"), ParseResult::Ok);
+        assert_eq!(check("('\\x7F' '\\n')"), ParseResult::Ok);
+        assert_eq!(check("('\\') 'a'"), ParseResult::Ok);
+        assert!(matches!(check("['\\') ']"), ParseResult::Corrupted(_)));
+        // An unclosed group is not an escape sequence, so its `{` is a delimiter.
+        assert_eq!(check("'\\u{7D' }"), ParseResult::Ok);
+
+        // A quote wider than the escape character.
+        let wide = Checker::standard().with_syntax(Syntax {
+            char_literal: Some('´'),
+            escape: Some('\\'),
+            ..Syntax::default()
+        });
+        let ParseResult::Corrupted(mismatch) = wide.check(&Field::new(1, "´\\n´)")).unwrap()
+        else {
+            panic!("expected corrupted text");
+        };
+        assert_eq!((mismatch.at.offset, mismatch.at.column), (6, 5));
+    }
+
+    #[test]
+    fn test_parse_syntax() {
+        let syntax = Syntax::parse("# shell-ish\nstring \"\nstring '\nline-comment #\n").unwrap();
+        assert_eq!(syntax.strings, ['"', '\'']);
+        assert_eq!(syntax.line_comments, ["#"]);
+        assert_eq!(
+            Syntax::parse("string \"\nchar '\nescape \\\nline-comment //\nblock-comment /* */")
+                .unwrap(),
+            Syntax::c()
+        );
+        let err = Syntax::parse("string \"\nchar ''").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.message.as_str()),
+            (2, 6, "expected a single character")
+        );
+        let err = Syntax::parse("block-comment {-").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        let err = Syntax::parse("  comment //").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+    }
+}
@@ -0,0 +1,194 @@
+mod delimiters;
+mod diagnostic;
+mod lexer;
+mod recovery;
+mod repair;
+mod scoring;
+mod stream;
+mod tree;
+
+pub use delimiters::{Delimiter, Delimiters, Token};
+pub use lexer::{Syntax, Tokens};
+pub use recovery::{Recovered, Resync};
+pub use repair::{Edit, Repair};
+pub use scoring::{Aggregate, Score, Scoring};
+pub use stream::{Snapshot, Validator};
+pub use tree::{Group, Tree};
+
+use crate::parse::{Field, ParseError};
+
+/// Where a token sits in the text being checked: its byte offset and length, and the line and
+/// 1-based column of its first character.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Span {
+    pub offset: usize,
+    pub len: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A closer that closed nothing, or closed something other than the innermost open delimiter.
+/// `expected` and `opener` are that delimiter's closer and where it was opened, if any.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Mismatch {
+    pub illegal: usize,
+    pub at: Span,
+    pub expected: Option<usize>,
+    pub opener: Option<Span>,
+}
+
+/// The outcome of checking a line. Delimiters are identified by their index in the table.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseResult {
+    Ok,
+    Corrupted(Mismatch),
+    Incomplete { stack: Vec<usize> },
+}
+
+// Closes the innermost open delimiter, unless closer `id` does not match it.
+fn close(stack: &mut Vec<(usize, Span)>, id: usize, at: Span) -> Option<Mismatch> {
+    match stack.pop() {
+        Some((open, _)) if open == id => None,
+        opened => Some(Mismatch {
+            illegal: id,
+            at,
+            expected: opened.map(|it| it.0),
+            opener: opened.map(|it| it.1),
+        }),
+    }
+}
+
+/// Checks that delimiters are balanced, as in day 10, for any table of delimiters.
+#[derive(Clone, Debug)]
+pub struct Checker {
+    delimiters: Delimiters,
+    syntax: Option<Syntax>,
+    scoring: Scoring,
+}
+
+impl Checker {
+    pub fn new(delimiters: Delimiters) -> Checker {
+        Checker {
+            scoring: Scoring::new(&delimiters),
+            delimiters,
+            syntax: None,
+        }
+    }
+
+    /// Checks source code instead of bare delimiters: see `Tokens`. A line may then be a whole
+    /// file, since positions are still reported by line and column.
+    pub fn with_syntax(mut self, syntax: Syntax) -> Checker {
+        self.syntax = Some(syntax);
+        self
+    }
+
+    pub fn standard() -> Checker {
+        Self::new(Delimiters::standard())
+    }
+
+    pub fn delimiters(&self) -> &Delimiters {
+        &self.delimiters
+    }
+
+    /// Scores lines some other way than the delimiter table and day 10 do. The scoring must be
+    /// for this checker's delimiters.
+    pub fn with_scoring(mut self, scoring: Scoring) -> Checker {
+        self.scoring = scoring;
+        self
+    }
+
+    pub fn scoring(&self) -> &Scoring {
+        &self.scoring
+    }
+
+    pub fn syntax(&self) -> Option<&Syntax> {
+        self.syntax.as_ref()
+    }
+
+    pub fn tokens<'a>(&self, line: &Field<'a>) -> Tokens<'_, 'a> {
+        Tokens::new(&self.delimiters, self.syntax.as_ref(), *line)
+    }
+
+    /// Checks a line up to its first corrupted closer. Without a syntax, anything that is not a
+    /// delimiter or whitespace is an error.
+    pub fn check(&self, line: &Field) -> Result<ParseResult, ParseError> {
+        let mut stack: Vec<(usize, Span)> = vec![];
+        for token in self.tokens(line) {
+            match token? {
+                (Token::Open(id), span) => stack.push((id, span)),
+                (Token::Close(id), at) => {
+                    if let Some(mismatch) = close(&mut stack, id, at) {
+                        return Ok(ParseResult::Corrupted(mismatch));
+                    }
+                }
+            }
+        }
+        if stack.is_empty() {
+            Ok(ParseResult::Ok)
+        } else {
+            Ok(ParseResult::Incomplete {
+                stack: stack.into_iter().map(|it| it.0).collect(),
+            })
+        }
+    }
+
+    /// The closers that finish an incomplete line, innermost first.
+    pub fn complete(&self, stack: &[usize]) -> Vec<usize> {
+        stack.iter().rev().copied().collect()
+    }
+
+    pub fn render(&self, closers: &[usize]) -> String {
+        closers
+            .iter()
+            .map(|id| self.delimiters.get(*id).close.as_str())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_delimiters() {
+        let checker = Checker::new(
+            Delimiters::parse("# a small DSL\nbegin end 10 1\n( ) 3 2\nbeginning ending 7 3\n")
+                .unwrap(),
+        );
+        let check = |s| checker.check(&Field::new(1, s)).unwrap();
+        assert_eq!(check("begin ( ) end"), ParseResult::Ok);
+        assert_eq!(
+            check("beginning(end"),
+            ParseResult::Corrupted(Mismatch {
+                illegal: 0,
+                at: Span {
+                    offset: 10,
+                    len: 3,
+                    line: 1,
+                    column: 11
+                },
+                expected: Some(1),
+                opener: Some(Span {
+                    offset: 9,
+                    len: 1,
+                    line: 1,
+                    column: 10
+                }),
+            })
+        );
+        let ParseResult::Incomplete { stack } = check("begin(beginning") else {
+            panic!("expected an incomplete line");
+        };
+        let completion = checker.complete(&stack);
+        assert_eq!(checker.render(&completion), "ending)end");
+        assert_eq!(
+            checker.scoring().completion_score(&completion),
+            Score::from(3 * 25 + 2 * 5 + 1)
+        );
+
+        let err = checker.check(&Field::new(4, "begin x")).unwrap_err();
+        assert_eq!((err.line, err.column), (4, 7));
+        let err = Delimiters::parse("( ) 3 1\n[ ) 5 2").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+}
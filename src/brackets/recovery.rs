@@ -0,0 +1,108 @@
+use super::{Checker, Mismatch, Span, Token};
+use crate::parse::{Field, ParseError};
+
+/// How checking carried on after a mismatch.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Resync {
+    /// The closer matched a delimiter further out, so the ones opened inside it are taken to be
+    /// missing their closers, listed innermost first.
+    Closed(Vec<usize>),
+    /// The closer matched nothing that was open, so it was treated as an extra token.
+    Skipped,
+}
+
+/// Every mismatch in a line, with how each was recovered from, and the closers still needed at
+/// the end of the line, innermost first.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Recovered {
+    pub errors: Vec<(Mismatch, Resync)>,
+    pub completion: Vec<usize>,
+}
+
+impl Recovered {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty() && self.completion.is_empty()
+    }
+}
+
+impl Checker {
+    /// Checks a whole line, carrying on past each mismatch instead of stopping at the first. The
+    /// first error is the one `check` reports.
+    pub fn check_all(&self, line: &Field) -> Result<Recovered, ParseError> {
+        let mut stack: Vec<(usize, Span)> = vec![];
+        // How many of each delimiter are open, so a closer matching none is skipped at once.
+        let mut open = vec![0; self.delimiters.len()];
+        let mut errors = vec![];
+        for token in self.tokens(line) {
+            let (id, at) = match token? {
+                (Token::Open(id), span) => {
+                    stack.push((id, span));
+                    open[id] += 1;
+                    continue;
+                }
+                (Token::Close(id), at) => (id, at),
+            };
+            let top = stack.last().copied();
+            if top.map(|it| it.0) == Some(id) {
+                stack.pop();
+                open[id] -= 1;
+                continue;
+            }
+            let mismatch = Mismatch {
+                illegal: id,
+                at,
+                expected: top.map(|it| it.0),
+                opener: top.map(|it| it.1),
+            };
+            let resync = match open[id] {
+                0 => Resync::Skipped,
+                _ => {
+                    let idx = stack.iter().rposition(|it| it.0 == id).unwrap();
+                    let closed: Vec<usize> = stack.drain(idx..).map(|it| it.0).collect();
+                    for id in &closed {
+                        open[*id] -= 1;
+                    }
+                    Resync::Closed(closed.into_iter().skip(1).rev().collect())
+                }
+            };
+            errors.push((mismatch, resync));
+        }
+        Ok(Recovered {
+            errors,
+            completion: stack.iter().rev().map(|it| it.0).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brackets::ParseResult;
+    use crate::examples;
+
+    #[test]
+    fn test_check_all() {
+        let checker = Checker::standard();
+        let check_all = |s| checker.check_all(&Field::new(1, s)).unwrap();
+
+        let recovered = check_all("[(<)]{>}(");
+        let resyncs: Vec<&Resync> = recovered.errors.iter().map(|it| &it.1).collect();
+        assert_eq!(resyncs, [&Resync::Closed(vec![3]), &Resync::Skipped]);
+        assert_eq!(recovered.errors[1].0.at.column, 7);
+        assert_eq!(checker.render(&recovered.completion), ")");
+        assert!(check_all("<[]>").is_ok());
+
+        // The first error found while recovering is always the one scored.
+        let input = examples::input(10, "example");
+        for line in crate::parse::lines(&input) {
+            let recovered = checker.check_all(&line).unwrap();
+            match checker.check(&line).unwrap() {
+                ParseResult::Corrupted(mismatch) => assert_eq!(recovered.errors[0].0, mismatch),
+                ParseResult::Incomplete { stack } => {
+                    assert_eq!(recovered.completion, checker.complete(&stack))
+                }
+                ParseResult::Ok => assert!(recovered.is_ok()),
+            }
+        }
+    }
+}
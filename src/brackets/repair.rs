@@ -0,0 +1,427 @@
+use super::{Checker, Resync, Span, Token};
+use crate::parse::{Field, ParseError};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Edit {
+    /// Inserts a delimiter at a byte offset of the original line.
+    Insert {
+        offset: usize,
+        token: Token,
+    },
+    Delete {
+        span: Span,
+        token: Token,
+    },
+    /// Replaces the delimiter at `span` with `token`.
+    Substitute {
+        span: Span,
+        token: Token,
+    },
+}
+
+/// The edits that stop a line being corrupted, and the line with them applied. Openers left
+/// unclosed at the end are not repaired, since `complete` already deals with them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Repair {
+    pub edits: Vec<Edit>,
+    pub repaired: String,
+}
+
+// Edits made, openers left for `complete` to close, then the summed score of the delimiters the
+// edits put in or take out.
+type Cost = (usize, usize, u64);
+
+fn add(a: Cost, b: Cost) -> Cost {
+    (a.0 + b.0, a.1 + b.1, a.2 + b.2)
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Choice {
+    // The first token is repaired on its own: an opener gets its closer, a closer is deleted.
+    Alone,
+    // The first token is paired with the token at this index.
+    Pair(usize),
+}
+
+struct Repairer<'c> {
+    checker: &'c Checker,
+    tokens: Vec<(Token, Span)>,
+    end: usize,
+    // `balanced[i][j]` is the cheapest way to balance tokens `i..j`.
+    balanced: Vec<Vec<(Cost, Choice)>>,
+}
+
+impl Repairer<'_> {
+    fn score(&self, token: Token) -> u64 {
+        self.checker.edit_score(token)
+    }
+
+    fn offset(&self, idx: usize) -> usize {
+        self.tokens.get(idx).map_or(self.end, |it| it.1.offset)
+    }
+
+    // The edit, if any, that turns tokens `i` and `k` into a matching pair.
+    fn pair(&self, i: usize, k: usize) -> Option<(Cost, Option<Edit>)> {
+        let ((first, first_span), (second, second_span)) = (self.tokens[i], self.tokens[k]);
+        let substitute = |span, token| {
+            let edit = Edit::Substitute { span, token };
+            Some(((1, 0, self.score(token)), Some(edit)))
+        };
+        match (first, second) {
+            (Token::Open(a), Token::Close(b)) if a == b => Some(((0, 0, 0), None)),
+            (Token::Open(a), Token::Close(b)) => {
+                if self.score(Token::Open(b)) < self.score(Token::Close(a)) {
+                    substitute(first_span, Token::Open(b))
+                } else {
+                    substitute(second_span, Token::Close(a))
+                }
+            }
+            (Token::Open(a), Token::Open(_)) => substitute(second_span, Token::Close(a)),
+            (Token::Close(_), Token::Close(b)) => substitute(first_span, Token::Open(b)),
+            (Token::Close(_), Token::Open(_)) => None,
+        }
+    }
+
+    fn alone(&self, i: usize, j: usize) -> (Cost, Edit) {
+        let (token, span) = self.tokens[i];
+        match token {
+            Token::Open(id) => (
+                (1, 0, self.score(token)),
+                Edit::Insert {
+                    offset: self.offset(j),
+                    token: Token::Close(id),
+                },
+            ),
+            Token::Close(_) => ((1, 0, self.score(token)), Edit::Delete { span, token }),
+        }
+    }
+
+    fn solve(&mut self) {
+        let n = self.tokens.len();
+        self.balanced = vec![vec![((0, 0, 0), Choice::Alone); n + 1]; n + 1];
+        for len in 1..=n {
+            for i in 0..=n - len {
+                let j = i + len;
+                let mut best = (
+                    add(self.alone(i, j).0, self.balanced[i + 1][j].0),
+                    Choice::Alone,
+                );
+                for k in i + 1..j {
+                    if let Some((cost, _)) = self.pair(i, k) {
+                        let inside = add(self.balanced[i + 1][k].0, self.balanced[k + 1][j].0);
+                        let cost = add(cost, inside);
+                        if cost < best.0 {
+                            best = (cost, Choice::Pair(k));
+                        }
+                    }
+                }
+                self.balanced[i][j] = best;
+            }
+        }
+    }
+
+    fn balance_edits(&self, i: usize, j: usize, edits: &mut Vec<Edit>) {
+        if i >= j {
+            return;
+        }
+        match self.balanced[i][j].1 {
+            Choice::Alone => {
+                // Closers inserted for inner openers go in first.
+                self.balance_edits(i + 1, j, edits);
+                edits.push(self.alone(i, j).1);
+            }
+            Choice::Pair(k) => {
+                edits.extend(self.pair(i, k).unwrap().1);
+                self.balance_edits(i + 1, k, edits);
+                self.balance_edits(k + 1, j, edits);
+            }
+        }
+    }
+
+    // A line that is not corrupted is balanced runs separated by openers that are still open, so
+    // the cheapest repair splits the tokens into such runs.
+    fn edits(&self) -> Vec<Edit> {
+        let n = self.tokens.len();
+        let mut prefix: Vec<(Cost, usize)> = vec![((0, 0, 0), 0); n + 1];
+        for j in 1..=n {
+            let mut best = (add(prefix[j - 1].0, self.balanced[j - 1][j].0), j - 1);
+            if matches!(self.tokens[j - 1].0, Token::Open(_)) {
+                let open = add(prefix[j - 1].0, (0, 1, 0));
+                if open < best.0 {
+                    best = (open, j);
+                }
+            }
+            for (i, (before, _)) in prefix.iter().enumerate().take(j - 1) {
+                let cost = add(*before, self.balanced[i][j].0);
+                if cost < best.0 {
+                    best = (cost, i);
+                }
+            }
+            prefix[j] = best;
+        }
+        let mut edits = vec![];
+        let mut j = n;
+        while j > 0 {
+            let i = prefix[j].1;
+            if i == j {
+                // An opener left open.
+                j -= 1;
+                continue;
+            }
+            let mut run = vec![];
+            self.balance_edits(i, j, &mut run);
+            edits.splice(0..0, run);
+            j = i;
+        }
+        edits
+    }
+}
+
+// The most tokens searched together for a repair.
+const MAX_SEARCHED: usize = 200;
+
+// The tokens with every stretch matched without error left out, tracking the stack the way
+// `check_all` does. What is left out is balanced, or open to the end of the line with no errors
+// after it, so edits to the rest never depend on it, and a line that is not corrupted keeps none.
+// The tokens kept are split into stretches wherever nothing is left open.
+fn corrupted(tokens: Vec<(Token, Span)>, ids: usize) -> Vec<Vec<(Token, Span)>> {
+    let mut kept = vec![false; tokens.len()];
+    let mut ends = vec![false; tokens.len()];
+    // The id and index of each open delimiter, and whether an error came after it.
+    let mut stack: Vec<(usize, usize, bool)> = vec![];
+    let mut open = vec![0; ids];
+    for (idx, (token, _)) in tokens.iter().enumerate() {
+        let id = match *token {
+            Token::Open(id) => {
+                stack.push((id, idx, false));
+                open[id] += 1;
+                continue;
+            }
+            Token::Close(id) => id,
+        };
+        let matched = match stack.last() {
+            Some(&(top, _, _)) if top == id => stack.pop(),
+            _ => {
+                kept[idx] = true;
+                // Everything below the first opener already marked was marked with it.
+                for (_, opener, dirty) in stack.iter_mut().rev().take_while(|it| !it.2) {
+                    kept[*opener] = true;
+                    *dirty = true;
+                }
+                let resync = match open[id] {
+                    0 => None,
+                    _ => stack.iter().rposition(|it| it.0 == id),
+                };
+                resync.map(|at| {
+                    for (drained, _, _) in &stack[at + 1..] {
+                        open[*drained] -= 1;
+                    }
+                    stack.drain(at..).next().unwrap()
+                })
+            }
+        };
+        if let Some((id, _, dirty)) = matched {
+            open[id] -= 1;
+            kept[idx] |= dirty;
+        }
+        ends[idx] = stack.is_empty();
+    }
+    let mut stretches = vec![];
+    let mut stretch = vec![];
+    for ((token, kept), end) in tokens.into_iter().zip(kept).zip(ends) {
+        if kept {
+            stretch.push(token);
+        }
+        if end && !stretch.is_empty() {
+            stretches.push(std::mem::take(&mut stretch));
+        }
+    }
+    if !stretch.is_empty() {
+        stretches.push(stretch);
+    }
+    stretches
+}
+
+fn edit_offset(edit: &Edit) -> usize {
+    match edit {
+        Edit::Insert { offset, .. } => *offset,
+        Edit::Delete { span, .. } | Edit::Substitute { span, .. } => span.offset,
+    }
+}
+
+// Insertions at an offset go before the token there, which may itself be edited.
+fn edit_order(edit: &Edit) -> (usize, bool) {
+    (edit_offset(edit), !matches!(edit, Edit::Insert { .. }))
+}
+
+impl Checker {
+    fn search_edits(&self, tokens: Vec<(Token, Span)>) -> Vec<Edit> {
+        // Nothing after the last token depends on the edits, so a closer left to the end goes
+        // straight after it.
+        let last = tokens[tokens.len() - 1].1;
+        let mut repairer = Repairer {
+            checker: self,
+            tokens,
+            end: last.offset + last.len,
+            balanced: vec![],
+        };
+        repairer.solve();
+        repairer.edits()
+    }
+
+    // The edits `check_all` recovers with: each closer it skips is deleted, and the closers it
+    // takes to be missing are inserted before the closer it resynchronised on.
+    fn greedy_edits(&self, line: &Field) -> Result<Vec<Edit>, ParseError> {
+        let mut edits = vec![];
+        for (mismatch, resync) in self.check_all(line)?.errors {
+            let at = mismatch.at;
+            match resync {
+                Resync::Skipped => edits.push(Edit::Delete {
+                    span: at,
+                    token: Token::Close(mismatch.illegal),
+                }),
+                Resync::Closed(ids) => edits.extend(ids.into_iter().map(|id| Edit::Insert {
+                    offset: at.offset,
+                    token: Token::Close(id),
+                })),
+            }
+        }
+        Ok(edits)
+    }
+
+    /// The fewest insertions, deletions and substitutions of delimiters that stop `line` being
+    /// corrupted, leaving alone delimiters matched with no error between them. Of repairs that
+    /// are as short, the one leaving the fewest delimiters open is preferred, then the one
+    /// touching the lowest-scoring delimiters.
+    ///
+    /// The search is quadratic in memory and cubic in time, so only a few hundred delimiters
+    /// around errors are searched together. Past that, the line is split where nothing is left
+    /// open, and the fewest edits are only found within each part: in a long run of `)` each
+    /// part may delete its last closer rather than pair it with the next. If errors run on for
+    /// longer with something always left open, nothing is searched and the line is repaired the
+    /// way `check_all` recovers, which is linear but may edit far more than needed.
+    pub fn repair(&self, line: &Field) -> Result<Repair, ParseError> {
+        let stretches = corrupted(
+            self.tokens(line).collect::<Result<_, _>>()?,
+            self.delimiters.len(),
+        );
+        let mut edits = vec![];
+        if stretches.iter().any(|it| it.len() > MAX_SEARCHED) {
+            edits = self.greedy_edits(line)?;
+        } else {
+            let mut searched = vec![];
+            for stretch in stretches {
+                if searched.len() + stretch.len() > MAX_SEARCHED {
+                    edits.extend(self.search_edits(std::mem::take(&mut searched)));
+                }
+                searched.extend(stretch);
+            }
+            if !searched.is_empty() {
+                edits.extend(self.search_edits(searched));
+            }
+        }
+        edits.sort_by_key(edit_order);
+        let text = |token: Token| match token {
+            Token::Open(id) => self.delimiters.get(id).open.as_str(),
+            Token::Close(id) => self.delimiters.get(id).close.as_str(),
+        };
+        let mut repaired = String::new();
+        let mut copied = 0;
+        for edit in &edits {
+            let offset = edit_offset(edit);
+            repaired.push_str(&line.text[copied..offset]);
+            copied = offset;
+            match edit {
+                Edit::Insert { token, .. } => repaired.push_str(text(*token)),
+                Edit::Delete { span, .. } => copied += span.len,
+                Edit::Substitute { span, token } => {
+                    repaired.push_str(text(*token));
+                    copied += span.len;
+                }
+            }
+        }
+        repaired.push_str(&line.text[copied..]);
+        Ok(Repair { edits, repaired })
+    }
+
+    fn edit_score(&self, token: Token) -> u64 {
+        match token {
+            Token::Open(id) | Token::Close(id) => self.delimiters.get(id).corruption_score as u64,
+        }
+    }
+
+    /// Scores a repair by the corruption score of each delimiter it inserts, deletes or
+    /// substitutes in, so a repair touching rarer delimiters scores higher.
+    pub fn repair_score(&self, repair: &Repair) -> u64 {
+        repair
+            .edits
+            .iter()
+            .map(|edit| match edit {
+                Edit::Insert { token, .. }
+                | Edit::Delete { token, .. }
+                | Edit::Substitute { token, .. } => self.edit_score(*token),
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brackets::ParseResult;
+    use crate::examples;
+
+    #[test]
+    fn test_repair() {
+        let checker = Checker::standard();
+        let repair = |s| checker.repair(&Field::new(1, s)).unwrap();
+
+        let fixed = repair("{([(<{}[<>[]}>{[]{[(<()>");
+        assert_eq!(fixed.repaired, "{([(<{}[<>[]]>{[]{[(<()>");
+        assert_eq!(fixed.edits.len(), 1);
+        assert_eq!(checker.repair_score(&fixed), 57);
+        assert_eq!(repair("[(<)]").repaired, "[(<>)]");
+        assert_eq!(repair("(]) >").repaired, "(() )");
+        assert_eq!(repair("[(<]").repaired, "[()]");
+        let fixed = repair("<(]>");
+        assert_eq!(fixed.repaired, "<()>");
+        assert_eq!(
+            fixed.edits,
+            [Edit::Substitute {
+                span: Span {
+                    offset: 2,
+                    len: 1,
+                    line: 1,
+                    column: 3
+                },
+                token: Token::Close(0)
+            }]
+        );
+        assert!(repair("[<>({").edits.is_empty());
+
+        // Long lines are searched a part at a time, or repaired greedily if they cannot be split.
+        let closers = ")".repeat(2000);
+        assert_eq!(repair(&closers).repaired, "()".repeat(1000));
+        let line = "(]".repeat(1000);
+        let fixed = repair(&line);
+        assert_eq!(fixed.edits.len(), 1000);
+        assert_eq!(fixed.repaired, "(".repeat(1000));
+
+        // Only the delimiters around errors are searched, so long lines repair quickly.
+        let line = format!("{{{}]{}", "(<>)[]".repeat(2000), "{}".repeat(2000));
+        let fixed = repair(&line);
+        assert_eq!(fixed.edits.len(), 1);
+        assert_eq!(fixed.repaired, line.replacen('{', "[", 1));
+        assert_eq!(repair(&"<()>".repeat(5000)).repaired, "<()>".repeat(5000));
+
+        // Every repaired line is no longer corrupted, and is never changed more than needed.
+        let input = examples::input(10, "example");
+        for line in crate::parse::lines(&input) {
+            let fixed = checker.repair(&line).unwrap();
+            let errors = checker.check_all(&line).unwrap().errors.len();
+            assert!(fixed.edits.len() <= errors);
+            let result = checker.check(&Field::new(1, &fixed.repaired)).unwrap();
+            assert!(!matches!(result, ParseResult::Corrupted(_)));
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use super::{Checker, Delimiters, ParseResult};
+use crate::parse::{self, Field, ParseError};
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+/// A score of any size, so a long completion cannot overflow it.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Score {
+    // Least significant first, without trailing zeros.
+    limbs: Vec<u64>,
+}
+
+impl Score {
+    /// `self * factor + term`.
+    pub fn mul_add(&mut self, factor: u64, term: u64) {
+        let mut carry = term as u128;
+        for limb in &mut self.limbs {
+            let value = *limb as u128 * factor as u128 + carry;
+            *limb = value as u64;
+            carry = value >> 64;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u64);
+        }
+        self.trim();
+    }
+
+    pub fn add(&mut self, other: &Score) {
+        let mut carry = false;
+        for idx in 0..self.limbs.len().max(other.limbs.len()) {
+            if idx == self.limbs.len() {
+                self.limbs.push(0);
+            }
+            let (sum, over) =
+                self.limbs[idx].overflowing_add(other.limbs.get(idx).map_or(0, |it| *it));
+            let (sum, carried) = sum.overflowing_add(carry as u64);
+            self.limbs[idx] = sum;
+            carry = over || carried;
+        }
+        if carry {
+            self.limbs.push(1);
+        }
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [value] => Some(value),
+            _ => None,
+        }
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    // Divides in place, returning the remainder.
+    fn div_rem(&mut self, divisor: u64) -> u64 {
+        let mut rem = 0u128;
+        for limb in self.limbs.iter_mut().rev() {
+            let value = (rem << 64) | *limb as u128;
+            *limb = (value / divisor as u128) as u64;
+            rem = value % divisor as u128;
+        }
+        self.trim();
+        rem as u64
+    }
+}
+
+impl From<u64> for Score {
+    fn from(value: u64) -> Score {
+        let mut score = Score::default();
+        score.mul_add(0, value);
+        score
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Score) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Score) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        // Nineteen digits at a time, the most a u64 holds.
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut rest = self.clone();
+        let mut chunks = vec![];
+        while !rest.limbs.is_empty() {
+            chunks.push(rest.div_rem(CHUNK));
+        }
+        let Some((first, others)) = chunks.split_last() else {
+            return write!(f, "0");
+        };
+        write!(f, "{}", first)?;
+        for chunk in others.iter().rev() {
+            write!(f, "{:019}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// How the scores of many lines are combined into one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Aggregate {
+    Sum,
+    Max,
+    /// The middle score, or the lower of the two middle ones.
+    Median,
+    /// The score below which this percentage of the others fall, rounding down.
+    Percentile(u32),
+}
+
+impl Aggregate {
+    /// Combines scores; only a sum of no scores has a value.
+    pub fn apply(&self, mut scores: Vec<Score>) -> Option<Score> {
+        if *self == Aggregate::Sum {
+            let mut total = Score::default();
+            for score in &scores {
+                total.add(score);
+            }
+            return Some(total);
+        }
+        let percent = match self {
+            Aggregate::Median => 50,
+            Aggregate::Percentile(percent) => *percent as usize,
+            _ => 100,
+        };
+        scores.sort_unstable();
+        let idx = scores.len().checked_sub(1)? * percent / 100;
+        Some(scores.swap_remove(idx))
+    }
+
+    // Parses the arguments of a `corrupted` or `incomplete` rule.
+    fn parse(rule: &Field, fields: &[Field]) -> Result<Aggregate, ParseError> {
+        match fields {
+            [it] if it.text == "sum" => Ok(Aggregate::Sum),
+            [it] if it.text == "max" => Ok(Aggregate::Max),
+            [it] if it.text == "median" => Ok(Aggregate::Median),
+            [it, percent] if it.text == "percentile" => {
+                let percent = percent.parse()?;
+                if percent > 100 {
+                    return Err(fields[1].error("expected a percentage up to 100"));
+                }
+                Ok(Aggregate::Percentile(percent))
+            }
+            [it, ..] => Err(it.error("expected sum, max, median or percentile <p>")),
+            [] => Err(rule.error(format!("expected {} <aggregate>", rule.text))),
+        }
+    }
+}
+
+/// How checked lines are scored: a weight per delimiter for finding its closer out of place, a
+/// weight per closer a completion adds, the base completion scores are counted in, and how the
+/// scores of corrupted and of incomplete lines are combined.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Scoring {
+    pub corruption: Vec<u64>,
+    pub completion: Vec<u64>,
+    pub base: u64,
+    pub corrupted: Aggregate,
+    pub incomplete: Aggregate,
+}
+
+impl Scoring {
+    /// The weights in the delimiter table, scored as in day 10.
+    pub fn new(delimiters: &Delimiters) -> Scoring {
+        let pairs = (0..delimiters.len()).map(|id| delimiters.get(id));
+        Scoring {
+            corruption: pairs.clone().map(|it| it.corruption_score as u64).collect(),
+            completion: pairs.map(|it| it.completion_score).collect(),
+            base: 5,
+            corrupted: Aggregate::Sum,
+            incomplete: Aggregate::Median,
+        }
+    }
+
+    /// Parses rules that change the day 10 scoring, one per line: `weight <delimiter>
+    /// <corruption> <completion>`, where either token of a pair names it, `base <n>`, and
+    /// `corrupted` or `incomplete` followed by `sum`, `max`, `median` or `percentile <p>`.
+    pub fn parse(input: &str, delimiters: &Delimiters) -> Result<Scoring, ParseError> {
+        let mut scoring = Scoring::new(delimiters);
+        for (line, fields) in parse::config_lines(input) {
+            let (rule, args) = (fields[0], &fields[1..]);
+            match (rule.text, args) {
+                ("weight", [token, corruption, completion]) => {
+                    let id = (0..delimiters.len())
+                        .find(|id| {
+                            let pair = delimiters.get(*id);
+                            pair.open == token.text || pair.close == token.text
+                        })
+                        .ok_or_else(|| {
+                            token.error(format!("{:?} is not a delimiter", token.text))
+                        })?;
+                    scoring.corruption[id] = corruption.parse()?;
+                    scoring.completion[id] = completion.parse()?;
+                }
+                ("weight", _) => {
+                    return Err(line.error("expected weight <delimiter> <corruption> <completion>"))
+                }
+                ("base", [base]) => scoring.base = base.parse()?,
+                ("base", _) => return Err(line.error("expected base <n>")),
+                ("corrupted", args) => scoring.corrupted = Aggregate::parse(&rule, args)?,
+                ("incomplete", args) => scoring.incomplete = Aggregate::parse(&rule, args)?,
+                _ => return Err(rule.error(format!("unknown rule {:?}", rule.text))),
+            }
+        }
+        Ok(scoring)
+    }
+
+    /// The weight of the illegal closer, if the line is corrupted.
+    pub fn corruption_score(&self, result: &ParseResult) -> Option<Score> {
+        match result {
+            ParseResult::Corrupted(mismatch) => Some(self.corruption[mismatch.illegal].into()),
+            _ => None,
+        }
+    }
+
+    /// The closers' weights read as the digits of a number in `base`, innermost first.
+    pub fn completion_score(&self, completion: &[usize]) -> Score {
+        let mut score = Score::default();
+        for id in completion {
+            score.mul_add(self.base, self.completion[*id]);
+        }
+        score
+    }
+
+    /// The combined score of the corrupted lines and of the incomplete ones. Only a sum has a
+    /// score when there are no lines of that kind.
+    pub fn aggregate(
+        &self,
+        checker: &Checker,
+        results: &[ParseResult],
+    ) -> (Option<Score>, Option<Score>) {
+        let corrupted = results.iter().filter_map(|it| self.corruption_score(it));
+        let incomplete = results.iter().filter_map(|it| match it {
+            ParseResult::Incomplete { stack } => {
+                Some(self.completion_score(&checker.complete(stack)))
+            }
+            _ => None,
+        });
+        (
+            self.corrupted.apply(corrupted.collect()),
+            self.incomplete.apply(incomplete.collect()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Field;
+
+    #[test]
+    fn test_score() {
+        let mut score = Score::from(u64::MAX);
+        score.mul_add(5, 4);
+        assert_eq!(score.to_string(), "92233720368547758079");
+        assert_eq!(score.to_u64(), None);
+        score.add(&Score::from(1));
+        assert_eq!(score.to_string(), "92233720368547758080");
+        assert!(score > Score::from(u64::MAX));
+        assert_eq!(Score::default().to_string(), "0");
+        assert_eq!(Score::from(42).to_u64(), Some(42));
+
+        // Sixty `>` complete to 5^60 - 1 in base 5 with weight 4, past what a u64 holds.
+        let checker = Checker::standard();
+        let line = "<".repeat(60);
+        let ParseResult::Incomplete { stack } = checker.check(&Field::new(1, &line)).unwrap()
+        else {
+            panic!("expected an incomplete line");
+        };
+        let score = checker
+            .scoring()
+            .completion_score(&checker.complete(&stack));
+        assert_eq!(
+            score.to_string(),
+            "867361737988403547205962240695953369140624"
+        );
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let scores: Vec<Score> = [7, 1, 5, 3, 9, 2].into_iter().map(Score::from).collect();
+        let apply = |aggregate: Aggregate| aggregate.apply(scores.clone()).unwrap().to_u64();
+        assert_eq!(apply(Aggregate::Sum), Some(27));
+        assert_eq!(apply(Aggregate::Max), Some(9));
+        assert_eq!(apply(Aggregate::Median), Some(3));
+        assert_eq!(apply(Aggregate::Percentile(90)), Some(7));
+        assert_eq!(apply(Aggregate::Percentile(0)), Some(1));
+        assert_eq!(Aggregate::Median.apply(vec![]), None);
+        assert_eq!(Aggregate::Sum.apply(vec![]), Some(Score::default()));
+    }
+
+    #[test]
+    fn test_parse_scoring() {
+        let delimiters = Delimiters::standard();
+        let scoring = Scoring::parse(
+            "# flatter\nweight ] 10 2\nweight } 20 3\nbase 4\ncorrupted max\nincomplete percentile 75\n",
+            &delimiters,
+        )
+        .unwrap();
+        assert_eq!(scoring.corruption, [3, 10, 20, 25137]);
+        assert_eq!(scoring.base, 4);
+        assert_eq!(scoring.corrupted, Aggregate::Max);
+        assert_eq!(scoring.incomplete, Aggregate::Percentile(75));
+        assert_eq!(
+            Scoring::parse("", &delimiters).unwrap(),
+            Scoring::new(&delimiters)
+        );
+
+        let checker = Checker::standard().with_scoring(scoring);
+        let results: Vec<ParseResult> = ["(]", "{)", "[<", "(", "<{"]
+            .iter()
+            .map(|it| checker.check(&Field::new(1, it)).unwrap())
+            .collect();
+        // Completions score "> ]" 4*4 + 2, ")" 1 and "} >" 3*4 + 4.
+        let (corrupted, incomplete) = checker.scoring().aggregate(&checker, &results);
+        assert_eq!(corrupted, Some(Score::from(10)));
+        assert_eq!(incomplete, Some(Score::from(16)));
+
+        let err = Scoring::parse("weight x 1 2", &delimiters).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 8));
+        let err = Scoring::parse("base 5\nincomplete percentile 101", &delimiters).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 23));
+        let err = Scoring::parse("corrupted mean", &delimiters).unwrap_err();
+        assert_eq!(err.column, 11);
+        let err = Scoring::parse("  incomplete", &delimiters).unwrap_err();
+        assert_eq!(
+            (err.column, err.message.as_str()),
+            (3, "expected incomplete <aggregate>")
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use super::lexer::Unfinished;
+use super::{close, Checker, Mismatch, ParseResult, Span, Token};
+use crate::parse::{Field, ParseError};
+
+/// How far a `Validator` has got, to be resumed later with the same checker.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Snapshot {
+    // Text that more input could still turn into a longer token or the end of a comment.
+    pending: String,
+    // The string or comment the text so far ends inside of, which is read as it arrives.
+    unfinished: Option<Unfinished>,
+    // Where `pending` starts in the whole text.
+    offset: usize,
+    line: usize,
+    column: usize,
+    stack: Vec<(usize, Span)>,
+    // Set once the text is known to be corrupted or malformed, after which input is ignored.
+    stopped: Option<Result<Mismatch, ParseError>>,
+}
+
+impl Snapshot {
+    /// The byte offset up to which the text has been checked.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// Checks text that arrives a chunk at a time, such as a large file or a buffer being typed into.
+/// Chunks may split tokens, strings and comments anywhere, and the text may span many lines, with
+/// spans giving offsets into the text as a whole.
+#[derive(Clone, Debug)]
+pub struct Validator<'c> {
+    checker: &'c Checker,
+    state: Snapshot,
+}
+
+impl<'c> Validator<'c> {
+    /// Checks the next chunk, returning the mismatch as soon as the text is known to be
+    /// corrupted, and on every push after that.
+    pub fn push(&mut self, chunk: &str) -> Result<Option<Mismatch>, ParseError> {
+        if self.state.stopped.is_none() {
+            self.state.pending.push_str(chunk);
+            self.read(true);
+        }
+        match &self.state.stopped {
+            None => Ok(None),
+            Some(Ok(mismatch)) => Ok(Some(*mismatch)),
+            Some(Err(err)) => Err(err.clone()),
+        }
+    }
+
+    /// The result of checking the text pushed so far, as if it had ended here.
+    pub fn result(&self) -> Result<ParseResult, ParseError> {
+        self.clone().finish()
+    }
+
+    /// Ends the text, giving the same result as `Checker::check` on all of it.
+    pub fn finish(mut self) -> Result<ParseResult, ParseError> {
+        if self.state.stopped.is_none() {
+            self.read(false);
+        }
+        match self.state.stopped {
+            Some(Ok(mismatch)) => Ok(ParseResult::Corrupted(mismatch)),
+            Some(Err(err)) => Err(err),
+            None if self.state.stack.is_empty() => Ok(ParseResult::Ok),
+            None => Ok(ParseResult::Incomplete {
+                stack: self.state.stack.into_iter().map(|it| it.0).collect(),
+            }),
+        }
+    }
+
+    /// Where the delimiters still open were opened, outermost first.
+    pub fn open(&self) -> impl Iterator<Item = &Span> {
+        self.state.stack.iter().map(|it| &it.1)
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        self.state.clone()
+    }
+
+    // Reads tokens from the pending text, then drops as much of it as was read.
+    fn read(&mut self, partial: bool) {
+        let Snapshot {
+            pending,
+            unfinished,
+            offset,
+            line,
+            column,
+            stack,
+            stopped,
+        } = &mut self.state;
+        let field = Field {
+            text: pending,
+            line: *line,
+            column: *column,
+        };
+        let mut tokens = self.checker.tokens(&field).continuing(unfinished.take());
+        if partial {
+            tokens = tokens.partial();
+        }
+        for token in &mut tokens {
+            match token {
+                Ok((Token::Open(id), mut span)) => {
+                    span.offset += *offset;
+                    stack.push((id, span));
+                }
+                Ok((Token::Close(id), mut at)) => {
+                    at.offset += *offset;
+                    if let Some(mismatch) = close(stack, id, at) {
+                        *stopped = Some(Ok(mismatch));
+                        break;
+                    }
+                }
+                Err(err) => {
+                    *stopped = Some(Err(err));
+                    break;
+                }
+            }
+        }
+        *unfinished = tokens.unfinished();
+        let (read, next_line, next_column) = tokens.position();
+        pending.drain(..read);
+        *offset += read;
+        (*line, *column) = (next_line, next_column);
+    }
+}
+
+impl Checker {
+    pub fn validator(&self) -> Validator<'_> {
+        self.resume(Snapshot {
+            pending: String::new(),
+            unfinished: None,
+            offset: 0,
+            line: 1,
+            column: 1,
+            stack: vec![],
+            stopped: None,
+        })
+    }
+
+    pub fn resume(&self, snapshot: Snapshot) -> Validator<'_> {
+        Validator {
+            checker: self,
+            state: snapshot,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brackets::{Delimiters, Syntax};
+    use crate::examples;
+
+    #[test]
+    fn test_chunks() {
+        // Corruption is found by the very push that brings in the illegal closer.
+        let checker = Checker::standard();
+        let input = examples::input(10, "example");
+        for line in input.lines() {
+            let expected = checker.check(&Field::new(1, line)).unwrap();
+            for size in 1..=5 {
+                let mut validator = checker.validator();
+                let mut found = None;
+                for (idx, chunk) in line.as_bytes().chunks(size).enumerate() {
+                    let pushed = validator.push(std::str::from_utf8(chunk).unwrap()).unwrap();
+                    found = found.or(pushed.map(|it| (idx, it)));
+                }
+                if let ParseResult::Corrupted(mismatch) = &expected {
+                    assert_eq!(found, Some((mismatch.at.offset / size, *mismatch)));
+                }
+                assert_eq!(validator.finish().unwrap(), expected);
+            }
+        }
+
+        let words = Checker::new(Delimiters::parse("begin end 10 1\n( ) 3 2").unwrap())
+            .with_syntax(Syntax::c());
+        let text = "begin\n  f(\"end)\", 'e') /* end */ // (\nend begin (";
+        assert_eq!(
+            words.check(&Field::new(1, text)).unwrap(),
+            ParseResult::Incomplete { stack: vec![0, 1] }
+        );
+        // Character literals are held back until it is clear whether they end where expected.
+        let texts = [
+            text,
+            "('\\') 'a'",
+            "'\\\n(begin{be'\\n'",
+            "<'\\\n[*'a'nd",
+            "('\\'' '\\\\' '\\u{7D}' '\\u{7D)'",
+            "'\\'')' ['\\x7F']",
+        ];
+        let standard = Checker::standard().with_syntax(Syntax::c());
+        for checker in [&words, &standard] {
+            for text in texts {
+                let expected = checker.check(&Field::new(1, text));
+                for split in 0..=text.len() {
+                    let mut validator = checker.validator();
+                    let _ = validator.push(&text[..split]);
+                    let snapshot = validator.snapshot();
+                    assert!(snapshot.offset() <= split);
+                    let mut resumed = checker.resume(snapshot);
+                    let _ = resumed.push(&text[split..]);
+                    assert_eq!(resumed.finish(), expected, "{:?} split at {}", text, split);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unfinished_comment() {
+        // Comments and strings are read as they arrive, rather than held back until they end.
+        let checker = Checker::standard().with_syntax(Syntax::c());
+        let mut validator = checker.validator();
+        validator.push("( /*").unwrap();
+        for _ in 0..1000 {
+            validator.push(" ) ] *").unwrap();
+            assert_eq!(validator.state.pending, "*");
+        }
+        validator.push("/ \"a\\").unwrap();
+        validator.push("\"]").unwrap();
+        assert!(validator.state.pending.is_empty());
+        assert_eq!(validator.push("\" )").unwrap(), None);
+        assert_eq!(validator.finish().unwrap(), ParseResult::Ok);
+    }
+
+    #[test]
+    fn test_stream_positions() {
+        let checker = Checker::standard();
+        let mut validator = checker.validator();
+        assert_eq!(validator.push("[(\n  <>").unwrap(), None);
+        assert_eq!(
+            validator.result().unwrap(),
+            ParseResult::Incomplete { stack: vec![1, 0] }
+        );
+        let open: Vec<(usize, usize)> = validator.open().map(|it| (it.line, it.column)).collect();
+        assert_eq!(open, [(1, 1), (1, 2)]);
+        let mismatch = validator.push("\n ]").unwrap().unwrap();
+        assert_eq!(
+            (mismatch.at.offset, mismatch.at.line, mismatch.at.column),
+            (9, 3, 2)
+        );
+        assert_eq!(validator.push("()").unwrap(), Some(mismatch));
+
+        let err = checker.validator().push("()\n x").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+    }
+}
@@ -0,0 +1,142 @@
+use super::{Checker, Span, Token};
+use crate::parse::{Field, ParseError};
+use std::fmt::Write;
+
+/// A matched pair and the pairs nested directly inside it. Top-level groups have depth 1.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Group {
+    pub delimiter: usize,
+    pub open: Span,
+    /// `None` if the line ended first and the closer comes from completing it.
+    pub close: Option<Span>,
+    pub depth: usize,
+    pub children: Vec<Group>,
+}
+
+impl Group {
+    fn descendants<'a>(&'a self, out: &mut Vec<&'a Group>) {
+        out.push(self);
+        for child in &self.children {
+            child.descendants(out);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Tree {
+    pub groups: Vec<Group>,
+}
+
+impl Tree {
+    /// Every group, each before the groups inside it.
+    pub fn iter(&self) -> impl Iterator<Item = &Group> {
+        let mut all = vec![];
+        for group in &self.groups {
+            group.descendants(&mut all);
+        }
+        all.into_iter()
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.iter().map(|it| it.depth).max().unwrap_or(0)
+    }
+
+    /// The group with the most groups directly inside it, the first one if there is a tie.
+    pub fn widest(&self) -> Option<&Group> {
+        let mut widest: Option<&Group> = None;
+        for group in self.iter() {
+            if group.children.len() > widest.map_or(0, |it| it.children.len()) {
+                widest = Some(group);
+            }
+        }
+        widest
+    }
+}
+
+impl Checker {
+    /// The nesting of a line that is balanced or only incomplete; a corrupted line has none.
+    pub fn tree(&self, line: &Field) -> Result<Option<Tree>, ParseError> {
+        let mut open: Vec<Group> = vec![];
+        let mut tree = Tree::default();
+        let close = |group: Group, open: &mut Vec<Group>, tree: &mut Tree| match open.last_mut() {
+            Some(parent) => parent.children.push(group),
+            None => tree.groups.push(group),
+        };
+        for token in self.tokens(line) {
+            match token? {
+                (Token::Open(id), span) => open.push(Group {
+                    delimiter: id,
+                    open: span,
+                    close: None,
+                    depth: open.len() + 1,
+                    children: vec![],
+                }),
+                (Token::Close(id), span) => match open.pop() {
+                    Some(mut group) if group.delimiter == id => {
+                        group.close = Some(span);
+                        close(group, &mut open, &mut tree);
+                    }
+                    _ => return Ok(None),
+                },
+            }
+        }
+        while let Some(group) = open.pop() {
+            close(group, &mut open, &mut tree);
+        }
+        Ok(Some(tree))
+    }
+
+    /// Draws one group per line, indented by depth, with the columns it spans. A group closed by
+    /// completion runs to `end`.
+    pub fn render_tree(&self, tree: &Tree) -> String {
+        let mut out = String::new();
+        for group in tree.iter() {
+            let delimiter = self.delimiters.get(group.delimiter);
+            let end = group
+                .close
+                .map_or("end".to_string(), |it| it.column.to_string());
+            writeln!(
+                out,
+                "{}{} {} {}..{}",
+                "  ".repeat(group.depth - 1),
+                delimiter.open,
+                delimiter.close,
+                group.open.column,
+                end
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree() {
+        let checker = Checker::standard();
+        let tree = |s| checker.tree(&Field::new(1, s)).unwrap();
+
+        let balanced = tree("[<>({}){}[([])<>]]()").unwrap();
+        assert_eq!(balanced.groups.len(), 2);
+        assert_eq!(balanced.max_depth(), 4);
+        assert_eq!(balanced.iter().count(), 10);
+        let widest = balanced.widest().unwrap();
+        assert_eq!((widest.delimiter, widest.children.len()), (1, 4));
+
+        let completed = tree("{(<>)[").unwrap();
+        assert_eq!(
+            checker.render_tree(&completed),
+            "\
+{ } 1..end
+  ( ) 2..5
+    < > 3..4
+  [ ] 6..end
+"
+        );
+        assert_eq!(tree("(]"), None);
+        assert_eq!(tree("").unwrap().max_depth(), 0);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::brackets::{Checker, ParseResult, Score, Scoring};
+use crate::parse;
+use crate::{InputError, InputSource, Line, Solution};
+
+/// Names a file of scoring rules, in the format `Scoring::parse` reads, to score day 10 with
+/// instead of the puzzle's own rules.
+pub const SCORING_VAR: &str = "AOC_DAY_10_SCORING";
+
+pub struct Day10;
+
+/// The outcome of each line, with the checker that scores them.
+#[derive(Clone, Debug)]
+pub struct Checked {
+    pub checker: Checker,
+    pub results: Vec<ParseResult>,
+}
+
+impl Day10 {
+    fn checker(&self) -> Result<Checker, InputError> {
+        let checker = Checker::standard();
+        let Some(path) = std::env::var_os(SCORING_VAR) else {
+            return Ok(checker);
+        };
+        let scoring = Scoring::parse(
+            &InputSource::File(path.into()).read()?,
+            checker.delimiters(),
+        )?;
+        Ok(checker.with_scoring(scoring))
+    }
+}
+
+impl Solution for Day10 {
+    const DAY: u16 = 10;
+    type Input = Checked;
+    type Answer1 = Score;
+    type Answer2 = Score;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, InputError> {
+        let checker = self.checker()?;
+        let results = parse::lines(input)
+            .iter()
+            .filter(|it| !it.is_empty())
+            .map(|it| Ok(checker.check(&it.trim())?))
+            .collect::<Result<_, InputError>>()?;
+        Ok(Checked { checker, results })
+    }
+
+    // Only the outcome of each line is kept, so long inputs never sit in memory.
+    fn parse_lines(
+        &self,
+        lines: &mut dyn Iterator<Item = Result<Line, InputError>>,
+    ) -> Result<Self::Input, InputError> {
+        let checker = self.checker()?;
+        let mut results = vec![];
+        for line in lines {
+            let line = line?;
+            if !line.field().is_empty() {
+                results.push(checker.check(&line.field().trim())?);
+            }
+        }
+        Ok(Checked { checker, results })
+    }
+
+    // With no lines of a kind to score, only a sum has a value, and anything else scores zero.
+    fn part1(&self, input: &Self::Input) -> Score {
+        let Checked { checker, results } = input;
+        let (corrupted, _) = checker.scoring().aggregate(checker, results);
+        corrupted.unwrap_or_default()
+    }
+
+    fn part2(&self, input: &Self::Input) -> Score {
+        let Checked { checker, results } = input;
+        let (_, incomplete) = checker.scoring().aggregate(checker, results);
+        incomplete.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brackets::Aggregate;
+    use crate::parse::Field;
+
+    fn completion_score(line: &str) -> Option<u64> {
+        let checker = Checker::standard();
+        match checker.check(&Field::new(1, line)).unwrap() {
+            ParseResult::Incomplete { stack } => checker
+                .scoring()
+                .completion_score(&checker.complete(&stack))
+                .to_u64(),
+            other => panic!("{} is not incomplete: {:?}", line, other),
+        }
+    }
+
+    #[test]
+    fn test_scoring() {
+        // Completed by "}}]])})]" and "])}>".
+        assert_eq!(completion_score("[({(<(())[]>[[{[]{<()<>>"), Some(288957));
+        assert_eq!(completion_score("<{([{{}}[<[[[<>{}]]]>[]]"), Some(294));
+    }
+
+    #[test]
+    fn test_no_lines_of_a_kind() {
+        let input = Day10.parse("[]\n(<>)").unwrap();
+        assert_eq!(Day10.part1(&input), Score::default());
+        assert_eq!(Day10.part2(&input), Score::default());
+
+        let mut input = Day10.parse("{()()()>\n<>").unwrap();
+        let mut scoring = input.checker.scoring().clone();
+        scoring.corrupted = Aggregate::Max;
+        input.checker = input.checker.with_scoring(scoring);
+        assert_eq!(Day10.part1(&input), Score::from(25137));
+        assert_eq!(Day10.part2(&input), Score::default());
+    }
+
+    #[test]
+    fn test_unexpected_character() {
+        let err = Day10.parse("[]\n(x)\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "malformed input: line 2, column 2: unexpected character 'x'"
+        );
+    }
+}
@@ -0,0 +1,353 @@
+use crate::parse::{self, Field, ParseError};
+use crate::{InputError, Solution};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Debug)]
+struct Element {
+    atom: char,
+    next: Option<Rc<RefCell<Element>>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rule {
+    first: char,
+    second: char,
+    insertion: char,
+}
+
+impl Rule {
+    fn parse(line: &Field) -> Result<Rule, ParseError> {
+        let parts = line.record(" -> ", 2)?;
+        let pair: Vec<char> = parts[0].text.chars().collect();
+        let insertion: Vec<char> = parts[1].text.chars().collect();
+        if pair.len() != 2 {
+            return Err(parts[0].error("expected a pair of elements"));
+        }
+        if insertion.len() != 1 {
+            return Err(parts[1].error("expected a single element"));
+        }
+        Ok(Rule {
+            first: pair[0],
+            second: pair[1],
+            insertion: insertion[0],
+        })
+    }
+}
+
+fn parse_input(input: &str) -> Result<(String, Vec<Rule>), InputError> {
+    let sections = parse::sections(input);
+    if sections.len() != 2 || sections[0].len() != 1 {
+        return Err(InputError::Malformed {
+            message: "expected a template line, a blank line, then insertion rules".to_string(),
+        });
+    }
+    let starting_pattern = sections[0][0].text.to_string();
+    let rules = sections[1]
+        .iter()
+        .map(Rule::parse)
+        .collect::<Result<_, _>>()?;
+    Ok((starting_pattern, rules))
+}
+
+fn build_start(pattern: &str) -> Rc<RefCell<Element>> {
+    let mut last: Option<Rc<RefCell<Element>>> = None;
+    for c in pattern.chars().rev() {
+        let curr = Element {
+            atom: c,
+            next: last.clone(),
+        };
+        last = Some(Rc::new(RefCell::new(curr)));
+    }
+    last.unwrap()
+}
+
+type RuleLookup = HashMap<char, HashMap<char, Rule>>;
+
+fn index_rules(rules: Vec<Rule>) -> RuleLookup {
+    let mut result: RuleLookup = HashMap::new();
+    for rule in rules {
+        result.entry(rule.first).or_default();
+        result
+            .get_mut(&rule.first)
+            .unwrap()
+            .insert(rule.second, rule.clone());
+    }
+    result
+}
+
+fn apply_rules_once(chain_head: Rc<RefCell<Element>>, rules: &RuleLookup) {
+    let mut first: Rc<RefCell<Element>> = chain_head.clone();
+    let mut second: Rc<RefCell<Element>> = first.borrow_mut().next.clone().unwrap();
+
+    loop {
+        let fc = first.borrow().atom;
+        let sc = second.borrow().atom;
+        let insertion = rules[&fc][&sc].insertion;
+        first.borrow_mut().next = Some(Rc::new(RefCell::new(Element {
+            atom: insertion,
+            next: Some(second.clone()),
+        })));
+        first = second;
+        if let Some(next) = &first.borrow().next {
+            second = next.clone();
+        } else {
+            break;
+        }
+    }
+}
+
+struct ChainCharIter {
+    curr_node: Option<Rc<RefCell<Element>>>,
+}
+
+impl ChainCharIter {
+    fn new(node: &Rc<RefCell<Element>>) -> ChainCharIter {
+        ChainCharIter {
+            curr_node: Some(node.clone()),
+        }
+    }
+}
+
+impl Iterator for ChainCharIter {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.curr_node.take().map(|node| {
+            self.curr_node = node.borrow().next.clone();
+            node.borrow().atom
+        })
+    }
+}
+
+fn spread_by_chain(starting_pattern: &str, rules: &[Rule], steps: usize) -> u64 {
+    let chain_head = build_start(starting_pattern);
+    let rule_index = index_rules(rules.to_vec());
+
+    for _ in 0..steps {
+        apply_rules_once(chain_head.clone(), &rule_index)
+    }
+
+    let mut counts: HashMap<char, u64> = HashMap::new();
+    for c in ChainCharIter::new(&chain_head) {
+        *counts.entry(c).or_default() += 1;
+    }
+    let most_common_count = counts.values().max().unwrap();
+    let least_common_count = counts.values().min().unwrap();
+    most_common_count - least_common_count
+}
+
+fn build_pairs(starting: &str) -> (HashMap<String, u64>, HashMap<char, u64>) {
+    let mut prev = starting.chars().next().unwrap();
+    let mut result = HashMap::new();
+    let mut counts = HashMap::new();
+    for c in starting.chars() {
+        let curr = *counts.get(&c).unwrap_or(&0);
+        counts.insert(c, curr + 1);
+    }
+    for c in starting.chars().skip(1) {
+        let pair = format!("{}{}", prev, c);
+        let curr = *result.get(&pair).unwrap_or(&0);
+        result.insert(pair, curr + 1);
+        prev = c;
+    }
+    (result, counts)
+}
+
+fn apply_rules_once_to_pairs(
+    curr_pairs: &HashMap<String, u64>,
+    counts: &mut HashMap<char, u64>,
+    rules: &[Rule],
+) -> HashMap<String, u64> {
+    let mut pairs = curr_pairs.clone();
+    for rule in rules {
+        let pair = format!("{}{}", rule.first, rule.second);
+        let curr_count = *curr_pairs.get(&pair).unwrap_or(&0);
+        if curr_count > 0 {
+            let new_pair_0 = format!("{}{}", rule.first, rule.insertion);
+            let new_pair_1 = format!("{}{}", rule.insertion, rule.second);
+            // Both halves may be the same pair, so each is added to in turn.
+            *pairs.entry(new_pair_0).or_default() += curr_count;
+            *pairs.entry(new_pair_1).or_default() += curr_count;
+            let curr_output_count = *pairs.get(&pair).unwrap_or(&0);
+            pairs.insert(pair, curr_output_count - curr_count);
+            let insertion_count = *counts.get(&rule.insertion).unwrap_or(&0);
+            counts.insert(rule.insertion, curr_count + insertion_count);
+        }
+    }
+    pairs
+}
+
+fn spread_by_pairs(starting_pattern: &str, rules: &[Rule], steps: usize) -> u64 {
+    let (mut atom_pairs, mut atom_counts) = build_pairs(starting_pattern);
+
+    for _ in 0..steps {
+        atom_pairs = apply_rules_once_to_pairs(&atom_pairs, &mut atom_counts, rules);
+    }
+    let mut pairs2: Vec<(char, u64)> = atom_counts.into_iter().collect();
+    pairs2.sort_unstable_by_key(|(_, count)| *count);
+    let most_common_count = pairs2.last().unwrap().1;
+    let least_common_count = pairs2.first().unwrap().1;
+    most_common_count - least_common_count
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u16 = 14;
+    type Input = (String, Vec<Rule>);
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, InputError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, (starting_pattern, rules): &Self::Input) -> u64 {
+        let spread = spread_by_chain(starting_pattern, rules, 10);
+        debug_assert_eq!(spread, spread_by_pairs(starting_pattern, rules, 10));
+        spread
+    }
+
+    fn part2(&self, (starting_pattern, rules): &Self::Input) -> u64 {
+        spread_by_pairs(starting_pattern, rules, 40)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::differential::{shrink_vec, Differential, Rng};
+
+    #[test]
+    fn test_grow_once() {
+        let start = build_start("NNCB");
+        let rules = vec![
+            Rule {
+                first: 'N',
+                second: 'N',
+                insertion: 'C',
+            },
+            Rule {
+                first: 'N',
+                second: 'C',
+                insertion: 'B',
+            },
+            Rule {
+                first: 'C',
+                second: 'B',
+                insertion: 'H',
+            },
+        ];
+        let i_rules = index_rules(rules);
+        apply_rules_once(start.clone(), &i_rules);
+        let result: String = ChainCharIter::new(&start).collect();
+        assert_eq!(result, "NCNBCHB".to_string())
+    }
+
+    #[test]
+    fn test_by_pairs() {
+        let (start, mut counts) = build_pairs("NNCB");
+        let rules = vec![
+            Rule {
+                first: 'N',
+                second: 'N',
+                insertion: 'C',
+            },
+            Rule {
+                first: 'N',
+                second: 'C',
+                insertion: 'B',
+            },
+            Rule {
+                first: 'C',
+                second: 'B',
+                insertion: 'H',
+            },
+        ];
+
+        let result = apply_rules_once_to_pairs(&start, &mut counts, &rules);
+        assert_eq!(counts[&'N'], 2);
+        assert_eq!(counts[&'C'], 2);
+        assert_eq!(counts[&'B'], 2);
+        assert_eq!(counts[&'H'], 1);
+        assert_eq!(result["BC"], 1);
+        assert_eq!(result["NN"], 0);
+    }
+
+    // A template, a rule for every pair of its elements and a number of steps.
+    type Polymer = (String, Vec<Rule>, usize);
+
+    fn random_polymer(rng: &mut Rng) -> Polymer {
+        let elements: Vec<char> = "BCHN".chars().take(rng.range(1..=4) as usize).collect();
+        let template = (0..rng.range(2..=8))
+            .map(|_| *rng.pick(&elements))
+            .collect();
+        let mut rules = vec![];
+        for first in &elements {
+            for second in &elements {
+                rules.push(Rule {
+                    first: *first,
+                    second: *second,
+                    insertion: *rng.pick(&elements),
+                });
+            }
+        }
+        (template, rules, rng.range(0..=6) as usize)
+    }
+
+    fn shrink_polymer((template, rules, steps): &Polymer) -> Vec<Polymer> {
+        let mut candidates = vec![];
+        if *steps > 0 {
+            candidates.push((template.clone(), rules.clone(), steps - 1));
+        }
+        // Rules for elements that can never appear are not needed.
+        let used: Vec<char> = template
+            .chars()
+            .chain(rules.iter().map(|it| it.insertion))
+            .collect();
+        let needed: Vec<Rule> = rules
+            .iter()
+            .filter(|it| used.contains(&it.first) && used.contains(&it.second))
+            .cloned()
+            .collect();
+        if needed.len() < rules.len() {
+            candidates.push((template.clone(), needed, *steps));
+        }
+        let chars: Vec<char> = template.chars().collect();
+        for smaller in shrink_vec(&chars, 2, |_| vec![]) {
+            candidates.push((smaller.into_iter().collect(), rules.clone(), *steps));
+        }
+        let simplest = template.chars().min().unwrap();
+        let simpler = shrink_vec(rules, rules.len(), |rule| {
+            (rule.insertion != simplest)
+                .then_some(Rule {
+                    insertion: simplest,
+                    ..rule.clone()
+                })
+                .into_iter()
+                .collect()
+        });
+        for rules in simpler {
+            candidates.push((template.clone(), rules, *steps));
+        }
+        candidates
+    }
+
+    #[test]
+    fn test_chain_and_pairs_agree() {
+        let result = Differential::new()
+            .implementation("chain", |(template, rules, steps): &Polymer| {
+                spread_by_chain(template, rules, *steps)
+            })
+            .implementation("pairs", |(template, rules, steps): &Polymer| {
+                spread_by_pairs(template, rules, *steps)
+            })
+            .shrink(shrink_polymer)
+            .run(14, 500, random_polymer);
+        if let Err(found) = result {
+            panic!("{}", found);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::parse::{self, ParseError};
+use crate::trace::Level;
+use crate::{event, InputError, Solution};
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+
+const PREFIX: &str = "target area: ";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Target {
+    x: RangeInclusive<i32>,
+    y: RangeInclusive<i32>,
+}
+
+// The sweep assumes the target is ahead of and below the launcher, as in every puzzle input.
+fn parse_target(input: &str) -> Result<Target, ParseError> {
+    let lines = parse::lines(input);
+    let line = lines
+        .iter()
+        .find(|it| !it.is_empty())
+        .map(|it| it.trim())
+        .ok_or_else(|| parse::Field::new(1, input).error("missing target area"))?;
+    if !line.text.starts_with(PREFIX) {
+        return Err(line.error(format!("expected {:?}", PREFIX)));
+    }
+    let ranges = line.split(PREFIX)[1].record(", ", 2)?;
+    let (x_key, x) = ranges[0].range::<i32>()?;
+    let (y_key, y) = ranges[1].range::<i32>()?;
+    if x_key != "x" || y_key != "y" {
+        return Err(ranges[0].error("expected x=<lo>..<hi>, y=<lo>..<hi>"));
+    }
+    if *x.start() <= 0 || x.start() > x.end() {
+        return Err(ranges[0].error("target must lie ahead of the launcher"));
+    }
+    if *y.end() >= 0 || y.start() > y.end() {
+        return Err(ranges[1].error("target must lie below the launcher"));
+    }
+    Ok(Target { x, y })
+}
+
+struct Probe {
+    pos_x: i32,
+    pos_y: i32,
+    vel_x: i32,
+    vel_y: i32,
+    max_y: i32,
+}
+
+impl Probe {
+    fn fire(vel_x: i32, vel_y: i32) -> Probe {
+        Probe {
+            pos_x: 0,
+            pos_y: 0,
+            max_y: 0,
+            vel_x,
+            vel_y,
+        }
+    }
+
+    fn step(&mut self) {
+        self.pos_x += self.vel_x;
+        self.pos_y += self.vel_y;
+        self.vel_x = match self.vel_x.cmp(&0) {
+            Ordering::Greater => self.vel_x - 1,
+            Ordering::Less => self.vel_x + 1,
+            Ordering::Equal => 0,
+        };
+        self.vel_y -= 1;
+        self.max_y = std::cmp::max(self.max_y, self.pos_y);
+        event!(
+            Level::Trace,
+            "step",
+            x = self.pos_x,
+            y = self.pos_y,
+            vel_x = self.vel_x,
+            vel_y = self.vel_y
+        );
+    }
+
+    fn hit_target(&self, target: &Target) -> bool {
+        target.x.contains(&self.pos_x) && target.y.contains(&self.pos_y)
+    }
+    fn overshot_target(&self, target: &Target) -> bool {
+        self.pos_x > *target.x.end() || self.pos_y < *target.y.start()
+    }
+    fn sim_until_done(&mut self, target: &Target) {
+        while !self.overshot_target(target) && !self.hit_target(target) {
+            self.step()
+        }
+    }
+}
+
+// Faster shots overshoot on the first step, or on the first step below the launcher.
+fn sweep(target: &Target) -> (i32, i32) {
+    let mut max_height: i32 = 0;
+    let mut hit_count: i32 = 0;
+    let min_y = *target.y.start();
+    for xvel in 1..=*target.x.end() {
+        for yvel in min_y..=-min_y {
+            let mut probe = Probe::fire(xvel, yvel);
+            probe.sim_until_done(target);
+            if probe.hit_target(target) {
+                hit_count += 1;
+                event!(
+                    Level::Debug,
+                    "hit",
+                    vel_x = xvel,
+                    vel_y = yvel,
+                    max_y = probe.max_y
+                );
+                max_height = std::cmp::max(max_height, probe.max_y);
+            }
+        }
+    }
+    (max_height, hit_count)
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u16 = 17;
+    type Input = Target;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(&self, input: &str) -> Result<Target, InputError> {
+        Ok(parse_target(input)?)
+    }
+
+    fn part1(&self, target: &Target) -> i32 {
+        sweep(target).0
+    }
+
+    fn part2(&self, target: &Target) -> i32 {
+        sweep(target).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace;
+
+    #[test]
+    fn test_parse_target() {
+        let target = parse_target("target area: x=20..30, y=-10..-5").unwrap();
+        assert_eq!((target.x, target.y), (20..=30, -10..=-5));
+        let err = parse_target("target area: x=20..30, y=5..10").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 24));
+        assert!(parse_target("x=20..30, y=-10..-5").is_err());
+    }
+
+    #[test]
+    fn test_trace_hits() {
+        let target = parse_target("target area: x=20..30, y=-10..-5").unwrap();
+        let hits = trace::capture(Level::Debug, || assert_eq!(sweep(&target), (45, 112)));
+        assert_eq!(hits.len(), 112);
+        assert!(hits.contains(&"DEBUG day_17 hit vel_x=6 vel_y=9 max_y=45".to_string()));
+        let steps = trace::capture(Level::Trace, || {
+            Probe::fire(7, 2).sim_until_done(&target);
+        });
+        assert_eq!(steps.len(), 7);
+        assert_eq!(steps[0], "TRACE day_17 step x=7 y=2 vel_x=6 vel_y=1");
+    }
+}
@@ -0,0 +1,214 @@
+use crate::memo::Memo;
+use crate::parse::{self, Field, ParseError};
+use crate::{InputError, Solution};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct GameState {
+    player_0_pos: u32,
+    player_1_pos: u32,
+    player_0_score: u32,
+    player_1_score: u32,
+    num_rolls: u32,
+    die_state: u32,
+    next_player: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+struct WinInfo {
+    player_0_wins: u64,
+    player_1_wins: u64,
+}
+
+impl std::ops::Add<WinInfo> for WinInfo {
+    type Output = WinInfo;
+    fn add(self: WinInfo, other: WinInfo) -> WinInfo {
+        WinInfo {
+            player_0_wins: self.player_0_wins + other.player_0_wins,
+            player_1_wins: self.player_1_wins + other.player_1_wins,
+        }
+    }
+}
+
+impl std::ops::AddAssign for WinInfo {
+    fn add_assign(&mut self, rhs: WinInfo) {
+        self.player_0_wins += rhs.player_0_wins;
+        self.player_1_wins += rhs.player_1_wins;
+    }
+}
+
+impl GameState {
+    // Positions are 0-based here, while the puzzle numbers the board from 1.
+    fn start(player_0_pos: u32, player_1_pos: u32) -> GameState {
+        GameState {
+            player_0_pos,
+            player_1_pos,
+            player_0_score: 0,
+            player_1_score: 0,
+            num_rolls: 0,
+            die_state: 0,
+            next_player: 0,
+        }
+    }
+
+    fn sim_to_win(&mut self) -> GameState {
+        let roll_total =
+            (self.die_state + 1) % 100 + (self.die_state + 2) % 100 + (self.die_state + 3) % 100;
+        self.die_state += 3;
+        self.die_state %= 100;
+        self.num_rolls += 3;
+        if self.next_player == 0 {
+            self.player_0_pos += roll_total;
+            self.player_0_pos %= 10;
+            self.player_0_score += self.player_0_pos + 1;
+        } else {
+            self.player_1_pos += roll_total;
+            self.player_1_pos %= 10;
+            self.player_1_score += self.player_1_pos + 1;
+        }
+        self.next_player += 1;
+        self.next_player %= 2;
+
+        if self.player_0_score >= 1000 || self.player_1_score >= 1000 {
+            *self
+        } else {
+            self.sim_to_win()
+        }
+    }
+
+    fn dirac_sim(&self, memo: &mut Memo<GameState, WinInfo>) -> WinInfo {
+        let mut info = WinInfo::default();
+        for roll_total in 1..=3 {
+            let num_rolls = self.num_rolls + 1;
+            let mut player_0_pos = self.player_0_pos;
+            let mut player_1_pos = self.player_1_pos;
+            if self.next_player == 0 {
+                player_0_pos += roll_total;
+                player_0_pos %= 10;
+            } else {
+                player_1_pos += roll_total;
+                player_1_pos %= 10;
+            }
+            let mut player_0_score = self.player_0_score;
+            let mut player_1_score = self.player_1_score;
+            let next_player = if num_rolls.is_multiple_of(3) && !num_rolls.is_multiple_of(2) {
+                assert!(self.next_player == 0);
+                player_0_score += player_0_pos + 1;
+                1
+            } else if num_rolls.is_multiple_of(3) {
+                assert!(self.next_player == 1);
+                player_1_score += player_1_pos + 1;
+                0
+            } else {
+                self.next_player
+            };
+
+            let next_state = GameState {
+                num_rolls,
+                player_0_score,
+                player_1_score,
+                player_0_pos,
+                player_1_pos,
+                next_player,
+                die_state: 0,
+            };
+
+            let this_win = if player_0_score >= 21 {
+                Some(WinInfo {
+                    player_0_wins: 1,
+                    player_1_wins: 0,
+                })
+            } else if player_1_score >= 21 {
+                Some(WinInfo {
+                    player_0_wins: 0,
+                    player_1_wins: 1,
+                })
+            } else {
+                None
+            };
+
+            info += match this_win {
+                Some(this_info) => this_info,
+                // Every roll adds to num_rolls, so a state can never lead back to itself.
+                None => memo
+                    .get_or_compute(next_state, |memo| next_state.dirac_sim(memo))
+                    .unwrap(),
+            };
+        }
+        info
+    }
+}
+
+fn parse_start(input: &str) -> Result<GameState, ParseError> {
+    let lines: Vec<Field> = parse::lines(input)
+        .into_iter()
+        .filter(|it| !it.is_empty())
+        .collect();
+    if lines.len() != 2 {
+        return Err(
+            Field::new(1, input).error("expected a starting position for each of 2 players")
+        );
+    }
+    let mut positions = [0; 2];
+    for (player, line) in lines.iter().enumerate() {
+        let fields = line.trim().record(": ", 2)?;
+        if fields[0].text != format!("Player {} starting position", player + 1) {
+            return Err(fields[0].error(format!(
+                "expected \"Player {} starting position: <n>\"",
+                player + 1
+            )));
+        }
+        let position: u32 = fields[1].parse()?;
+        if !(1..=10).contains(&position) {
+            return Err(fields[1].error("positions run from 1 to 10"));
+        }
+        positions[player] = position - 1;
+    }
+    Ok(GameState::start(positions[0], positions[1]))
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u16 = 21;
+    type Input = GameState;
+    type Answer1 = u32;
+    type Answer2 = u64;
+
+    fn parse(&self, input: &str) -> Result<GameState, InputError> {
+        Ok(parse_start(input)?)
+    }
+
+    fn part1(&self, start: &GameState) -> u32 {
+        let mut state = *start;
+        let win = state.sim_to_win();
+        win.num_rolls
+            * if win.player_0_score >= 1000 {
+                win.player_1_score
+            } else {
+                win.player_0_score
+            }
+    }
+
+    fn part2(&self, start: &GameState) -> u64 {
+        let base_info = start.dirac_sim(&mut Memo::new());
+        std::cmp::max(base_info.player_0_wins, base_info.player_1_wins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_start() {
+        let start =
+            parse_start("Player 1 starting position: 4\nPlayer 2 starting position: 8").unwrap();
+        assert_eq!((start.player_0_pos, start.player_1_pos), (3, 7));
+        let err = parse_start("Player 1 starting position: 4\nPlayer 2 starting position: 11")
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 29));
+        assert!(
+            parse_start("Player 2 starting position: 4\nPlayer 1 starting position: 8").is_err()
+        );
+    }
+}
@@ -0,0 +1,451 @@
+use super::day_23_part_2;
+use crate::search::{self, Path, SearchState};
+use crate::{InputError, Solution};
+use std::fmt::{self, Display, Formatter};
+
+const ROOM_SIZE: u8 = 2;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Position {
+    Hallway(u8),
+    RoomA(u8),
+    RoomB(u8),
+    RoomC(u8),
+    RoomD(u8),
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        let short_code = match self {
+            Hallway(..) => "H",
+            RoomA(..) => "A",
+            RoomB(..) => "B",
+            RoomC(..) => "C",
+            RoomD(..) => "D",
+        };
+        write!(f, "{}{}", short_code, self.index())
+    }
+}
+
+impl Position {
+    fn index(&self) -> u8 {
+        match self {
+            Hallway(p) => *p,
+            RoomA(p) => *p,
+            RoomB(p) => *p,
+            RoomC(p) => *p,
+            RoomD(p) => *p,
+        }
+    }
+    // The hallway column outside a room.
+    fn door(&self) -> Option<u8> {
+        match self {
+            Hallway(_) => None,
+            RoomA(_) => Some(2),
+            RoomB(_) => Some(4),
+            RoomC(_) => Some(6),
+            RoomD(_) => Some(8),
+        }
+    }
+    fn at_depth(&self, depth: u8) -> Position {
+        match self {
+            Hallway(_) => *self,
+            RoomA(_) => RoomA(depth),
+            RoomB(_) => RoomB(depth),
+            RoomC(_) => RoomC(depth),
+            RoomD(_) => RoomD(depth),
+        }
+    }
+    fn occupant(&self, pods: &[Amphipod]) -> Option<Amphipod> {
+        pods.iter().find(|it| &it.pos == self).cloned()
+    }
+
+    fn occupied(&self, positions: &[Amphipod]) -> bool {
+        self.occupant(positions).is_some()
+    }
+
+    fn is_in_door(&self) -> bool {
+        matches!(self, Hallway(2) | Hallway(4) | Hallway(6) | Hallway(8))
+    }
+}
+
+use Position::*;
+
+trait MovementCost {
+    fn cost(&self) -> u32;
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum AmphipodType {
+    Amber,
+    Bronze,
+    Copper,
+    Desert,
+}
+
+use AmphipodType::*;
+
+impl AmphipodType {
+    fn from_char(c: char) -> Option<AmphipodType> {
+        match c {
+            'A' => Some(Amber),
+            'B' => Some(Bronze),
+            'C' => Some(Copper),
+            'D' => Some(Desert),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AmphipodType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{}",
+            match self {
+                Amber => "A",
+                Bronze => "B",
+                Copper => "C",
+                Desert => "D",
+            }
+        )
+    }
+}
+
+impl MovementCost for AmphipodType {
+    fn cost(&self) -> u32 {
+        match self {
+            Amber => 1,
+            Bronze => 10,
+            Copper => 100,
+            Desert => 1000,
+        }
+    }
+}
+
+type AmphipodID = u8;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct Amphipod {
+    id: AmphipodID,
+    t: AmphipodType,
+    pos: Position,
+}
+
+impl MovementCost for Amphipod {
+    fn cost(&self) -> u32 {
+        self.t.cost()
+    }
+}
+
+impl Amphipod {
+    fn new(id: AmphipodID, t: AmphipodType, pos: Position) -> Amphipod {
+        Amphipod { id, t, pos }
+    }
+    fn is_room_match(&self, position: &Position) -> bool {
+        match self.t {
+            Amber => matches!(position, RoomA(..)),
+            Bronze => matches!(position, RoomB(..)),
+            Copper => matches!(position, RoomC(..)),
+            Desert => matches!(position, RoomD(..)),
+        }
+    }
+    fn is_home(&self) -> bool {
+        self.is_room_match(&self.pos)
+    }
+    fn home(&self, depth: u8) -> Position {
+        match self.t {
+            Amber => RoomA(depth),
+            Bronze => RoomB(depth),
+            Copper => RoomC(depth),
+            Desert => RoomD(depth),
+        }
+    }
+    fn door(&self) -> u8 {
+        self.home(0).door().unwrap()
+    }
+    fn home_unoccupied_or_match(&self, pods: &[Amphipod]) -> bool {
+        (0..ROOM_SIZE).all(|depth| {
+            self.home(depth)
+                .occupant(pods)
+                .map(|it| it.is_home())
+                .unwrap_or(true)
+        })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct State {
+    amphipods: Vec<Amphipod>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Move {
+    source: Amphipod,
+    dest: Amphipod,
+    score: u32,
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{}({}): {} -> {} ",
+            self.source.id, self.source.t, self.source.pos, self.dest.pos
+        )
+    }
+}
+
+fn pos_to_num(pod: &Amphipod) -> u64 {
+    let pos = pod.pos;
+    (match pos {
+        Hallway(num) => num,
+        RoomA(num) => 11 + num,
+        RoomB(num) => 13 + num,
+        RoomC(num) => 15 + num,
+        RoomD(num) => 17 + num,
+    }) as u64
+}
+
+impl State {
+    fn pos_hash(&self) -> u64 {
+        self.amphipods
+            .iter()
+            .fold(0u64, |acc, el| acc + (pos_to_num(el) << (7 * el.id)))
+    }
+    fn is_complete(&self) -> bool {
+        self.amphipods.iter().all(|it| it.is_home())
+    }
+
+    // A lower bound on the remaining cost: every amphipod outside its room must at least climb
+    // out to the hallway, walk to its room's door and step in.
+    fn min_remaining_cost(&self) -> u64 {
+        self.amphipods
+            .iter()
+            .filter(|it| !it.is_home())
+            .map(|it| {
+                let (column, climb) = match it.pos {
+                    Hallway(n) => (n, 0),
+                    RoomA(depth) => (2, ROOM_SIZE - depth),
+                    RoomB(depth) => (4, ROOM_SIZE - depth),
+                    RoomC(depth) => (6, ROOM_SIZE - depth),
+                    RoomD(depth) => (8, ROOM_SIZE - depth),
+                };
+                let door = match it.t {
+                    Amber => 2,
+                    Bronze => 4,
+                    Copper => 6,
+                    Desert => 8,
+                };
+                (climb + column.abs_diff(door) + 1) as u64 * it.cost() as u64
+            })
+            .sum()
+    }
+
+    fn is_settled(&self, amphipod: &Amphipod) -> bool {
+        amphipod.is_home()
+            && (0..amphipod.pos.index()).all(|depth| {
+                amphipod
+                    .home(depth)
+                    .occupant(&self.amphipods)
+                    .map(|it| it.is_home())
+                    .unwrap_or_default()
+            })
+    }
+
+    // Whether the hallway is empty between `from` (exclusive) and `to` (inclusive).
+    fn hallway_clear(&self, from: u8, to: u8) -> bool {
+        (from.min(to)..=from.max(to))
+            .filter(|it| *it != from)
+            .all(|it| !Hallway(it).occupied(&self.amphipods))
+    }
+
+    // Amphipods only ever stop in the hallway or in their own room, so each move takes an
+    // amphipod out of a room to a hallway spot, or from the hallway straight into its room.
+    fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = vec![];
+        for amphipod in self.amphipods.iter() {
+            let to = |pos: Position, steps: u8| Move {
+                source: *amphipod,
+                dest: Amphipod { pos, ..*amphipod },
+                score: steps as u32 * amphipod.cost(),
+            };
+            match amphipod.pos {
+                Hallway(column) => {
+                    if !amphipod.home_unoccupied_or_match(&self.amphipods)
+                        || !self.hallway_clear(column, amphipod.door())
+                    {
+                        continue;
+                    }
+                    let depth = (0..ROOM_SIZE)
+                        .find(|it| !amphipod.home(*it).occupied(&self.amphipods))
+                        .unwrap();
+                    let steps = column.abs_diff(amphipod.door()) + ROOM_SIZE - depth;
+                    // Going home is never worse than any other move, so take it straight away.
+                    return vec![to(amphipod.home(depth), steps)];
+                }
+                room => {
+                    let door = room.door().unwrap();
+                    let blocked = (room.index() + 1..ROOM_SIZE)
+                        .any(|depth| room.at_depth(depth).occupied(&self.amphipods));
+                    if blocked || self.is_settled(amphipod) {
+                        continue;
+                    }
+                    for column in (0..=10).filter(|it| !Hallway(*it).is_in_door()) {
+                        if self.hallway_clear(door, column) {
+                            let steps = ROOM_SIZE - room.index() + column.abs_diff(door);
+                            moves.push(to(Hallway(column), steps));
+                        }
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    fn apply_move(&self, mv: Move) -> State {
+        State {
+            amphipods: self
+                .amphipods
+                .iter()
+                .map(|it| if it.id == mv.source.id { mv.dest } else { *it })
+                .collect(),
+        }
+    }
+}
+
+impl SearchState for State {
+    type Key = u64;
+    type Move = Move;
+
+    fn key(&self) -> u64 {
+        self.pos_hash()
+    }
+
+    fn successors(&self) -> Vec<(Move, State, u64)> {
+        self.legal_moves()
+            .into_iter()
+            .map(|mv| (mv, self.apply_move(mv), mv.score as u64))
+            .collect()
+    }
+
+    fn is_goal(&self) -> bool {
+        self.is_complete()
+    }
+}
+
+fn solve(from_state: State) -> Option<Path<State>> {
+    search::astar(from_state, State::min_remaining_cost)
+}
+
+const ROOMS: [fn(u8) -> Position; 4] = [RoomA, RoomB, RoomC, RoomD];
+
+/// The amphipods in each room as drawn in the diagram, top row first.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Burrow {
+    pub(super) rows: [[char; 4]; 2],
+}
+
+fn parse_burrow(input: &str) -> Result<Burrow, InputError> {
+    let malformed = |message: &str| InputError::Malformed {
+        message: message.to_string(),
+    };
+    let mut rows = vec![];
+    for line in input.lines() {
+        let cells: Vec<char> = line.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+        if cells.is_empty() {
+            continue;
+        }
+        if cells.len() != 4 || cells.iter().any(|c| AmphipodType::from_char(*c).is_none()) {
+            return Err(malformed(&format!("bad burrow row {:?}", line.trim())));
+        }
+        rows.push([cells[0], cells[1], cells[2], cells[3]]);
+    }
+    let rows: [[char; 4]; 2] = rows
+        .try_into()
+        .map_err(|_| malformed("expected two rows of amphipods"))?;
+    for t in ['A', 'B', 'C', 'D'] {
+        if rows.iter().flatten().filter(|it| **it == t).count() != 2 {
+            return Err(malformed(&format!("expected two amphipods of type {}", t)));
+        }
+    }
+    Ok(Burrow { rows })
+}
+
+impl Burrow {
+    fn initial_state(&self) -> State {
+        let [top, bottom] = self.rows;
+        let mut amphipods = vec![];
+        for (room, pos) in ROOMS.iter().enumerate() {
+            for (depth, row) in [bottom, top].iter().enumerate() {
+                amphipods.push(Amphipod::new(
+                    amphipods.len() as AmphipodID,
+                    AmphipodType::from_char(row[room]).unwrap(),
+                    pos(depth as u8),
+                ));
+            }
+        }
+        State { amphipods }
+    }
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u16 = 23;
+    type Input = Burrow;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Burrow, InputError> {
+        parse_burrow(input)
+    }
+
+    fn part1(&self, burrow: &Burrow) -> u32 {
+        solve(burrow.initial_state()).unwrap().cost as u32
+    }
+
+    fn part2(&self, burrow: &Burrow) -> u32 {
+        day_23_part_2::best_score(burrow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples;
+
+    #[test]
+    fn test_parse_burrow() {
+        let burrow = parse_burrow(&examples::input(23, "example")).unwrap();
+        assert_eq!(burrow.rows, [['B', 'C', 'B', 'D'], ['A', 'D', 'C', 'A']]);
+        assert!(parse_burrow("###A#B#C#D###").is_err());
+        assert!(parse_burrow("###A#B#C#E###\n  #A#B#C#D#").is_err());
+        assert!(parse_burrow("###A#A#C#D###\n  #B#B#C#D#").is_ok());
+        assert!(parse_burrow("###A#A#C#D###\n  #A#B#C#D#").is_err());
+    }
+
+    #[test]
+    fn test_sorted_burrow() {
+        let burrow = parse_burrow(&examples::input(23, "sorted")).unwrap();
+        assert_eq!(Day23.part1(&burrow), 0);
+        // Only the amphipods in rooms B and C have to swap.
+        let burrow = parse_burrow(&examples::input(23, "swapped")).unwrap();
+        assert_eq!(Day23.part1(&burrow), 1140);
+    }
+
+    #[test]
+    fn test_search_agrees() {
+        let burrow = parse_burrow(&examples::input(23, "example")).unwrap();
+        let guided = solve(burrow.initial_state()).unwrap();
+        let cheapest = search::dijkstra(burrow.initial_state()).unwrap();
+        assert_eq!((guided.cost, cheapest.cost), (12521, 12521));
+        assert!(guided.expanded <= cheapest.expanded);
+    }
+
+    #[test]
+    fn test_unfolded_burrow() {
+        let burrow = parse_burrow(&examples::input(23, "example")).unwrap();
+        assert_eq!(Day23.part2(&burrow), 44169);
+    }
+}
@@ -0,0 +1,432 @@
+use super::day_23::Burrow;
+use crate::search::{self, SearchState};
+use std::fmt::{self, Display, Formatter};
+
+const ROOM_SIZE: u8 = 4;
+const ROOM_SIZE_M1: u8 = ROOM_SIZE - 1;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Position {
+    Hallway(u8),
+    RoomA(u8),
+    RoomB(u8),
+    RoomC(u8),
+    RoomD(u8),
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        let short_code = match self {
+            Hallway(..) => "H",
+            RoomA(..) => "A",
+            RoomB(..) => "B",
+            RoomC(..) => "C",
+            RoomD(..) => "D",
+        };
+        write!(f, "{}{}", short_code, self.index())
+    }
+}
+
+impl Position {
+    fn index(&self) -> u8 {
+        match self {
+            Hallway(p) => *p,
+            RoomA(p) => *p,
+            RoomB(p) => *p,
+            RoomC(p) => *p,
+            RoomD(p) => *p,
+        }
+    }
+    // The hallway column outside a room.
+    fn door(&self) -> Option<u8> {
+        match self {
+            Hallway(_) => None,
+            RoomA(_) => Some(2),
+            RoomB(_) => Some(4),
+            RoomC(_) => Some(6),
+            RoomD(_) => Some(8),
+        }
+    }
+    fn at_depth(&self, depth: u8) -> Position {
+        match self {
+            Hallway(_) => *self,
+            RoomA(_) => RoomA(depth),
+            RoomB(_) => RoomB(depth),
+            RoomC(_) => RoomC(depth),
+            RoomD(_) => RoomD(depth),
+        }
+    }
+    fn occupant(&self, pods: &[Amphipod]) -> Option<Amphipod> {
+        pods.iter().find(|it| &it.pos == self).cloned()
+    }
+
+    fn occupied(&self, positions: &[Amphipod]) -> bool {
+        self.occupant(positions).is_some()
+    }
+
+    fn is_in_door(&self) -> bool {
+        matches!(self, Hallway(2) | Hallway(4) | Hallway(6) | Hallway(8))
+    }
+}
+
+use Position::*;
+
+trait MovementCost {
+    fn cost(&self) -> u32;
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum AmphipodType {
+    Amber,
+    Bronze,
+    Copper,
+    Desert,
+}
+
+use AmphipodType::*;
+
+impl AmphipodType {
+    // Rows have already been validated by the part 1 parser.
+    fn from_char(c: char) -> AmphipodType {
+        match c {
+            'A' => Amber,
+            'B' => Bronze,
+            'C' => Copper,
+            _ => Desert,
+        }
+    }
+
+    fn id(&self) -> u8 {
+        match self {
+            Amber => 0,
+            Bronze => 1,
+            Copper => 2,
+            Desert => 3,
+        }
+    }
+}
+
+impl Display for AmphipodType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{}",
+            match self {
+                Amber => "A",
+                Bronze => "B",
+                Copper => "C",
+                Desert => "D",
+            }
+        )
+    }
+}
+
+impl MovementCost for AmphipodType {
+    fn cost(&self) -> u32 {
+        match self {
+            Amber => 1,
+            Bronze => 10,
+            Copper => 100,
+            Desert => 1000,
+        }
+    }
+}
+
+type AmphipodID = u8;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct Amphipod {
+    id: AmphipodID,
+    t: AmphipodType,
+    pos: Position,
+}
+
+impl MovementCost for Amphipod {
+    fn cost(&self) -> u32 {
+        self.t.cost()
+    }
+}
+
+impl Amphipod {
+    fn new(id: AmphipodID, t: AmphipodType, pos: Position) -> Amphipod {
+        Amphipod { id, t, pos }
+    }
+    fn is_room_match(&self, position: &Position) -> bool {
+        match self.t {
+            Amber => matches!(position, RoomA(..)),
+            Bronze => matches!(position, RoomB(..)),
+            Copper => matches!(position, RoomC(..)),
+            Desert => matches!(position, RoomD(..)),
+        }
+    }
+    fn is_home(&self) -> bool {
+        self.is_room_match(&self.pos)
+    }
+    fn home(&self, depth: u8) -> Position {
+        match self.t {
+            Amber => RoomA(depth),
+            Bronze => RoomB(depth),
+            Copper => RoomC(depth),
+            Desert => RoomD(depth),
+        }
+    }
+    fn door(&self) -> u8 {
+        self.home(0).door().unwrap()
+    }
+    fn home_unoccupied_or_match(&self, pods: &[Amphipod]) -> bool {
+        (0..ROOM_SIZE).all(|depth| {
+            self.home(depth)
+                .occupant(pods)
+                .map(|it| it.is_home())
+                .unwrap_or(true)
+        })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct State {
+    amphipods: Vec<Amphipod>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Move {
+    source: Amphipod,
+    dest: Amphipod,
+    score: u32,
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{}({}): {} -> {} ",
+            self.source.id, self.source.t, self.source.pos, self.dest.pos
+        )
+    }
+}
+
+fn pos_to_num(pod: &Amphipod) -> u128 {
+    let pos = pod.pos;
+    (match pos {
+        Hallway(num) => num,
+        RoomA(num) => 11 + (Amber.id() * ROOM_SIZE) + num,
+        RoomB(num) => 11 + (Bronze.id() * ROOM_SIZE) + num,
+        RoomC(num) => 11 + (Copper.id() * ROOM_SIZE) + num,
+        RoomD(num) => 11 + (Desert.id() * ROOM_SIZE) + num,
+    }) as u128
+}
+
+impl State {
+    fn pos_hash(&self) -> u128 {
+        let mut a_s: Vec<u128> = self
+            .amphipods
+            .iter()
+            .filter(|it| matches!(it.t, Amber))
+            .map(pos_to_num)
+            .collect();
+        a_s.sort_unstable();
+        let mut b_s: Vec<u128> = self
+            .amphipods
+            .iter()
+            .filter(|it| matches!(it.t, Bronze))
+            .map(pos_to_num)
+            .collect();
+        b_s.sort_unstable();
+        let mut c_s: Vec<u128> = self
+            .amphipods
+            .iter()
+            .filter(|it| matches!(it.t, Copper))
+            .map(pos_to_num)
+            .collect();
+        c_s.sort_unstable();
+        let mut d_s: Vec<u128> = self
+            .amphipods
+            .iter()
+            .filter(|it| matches!(it.t, Desert))
+            .map(pos_to_num)
+            .collect();
+        d_s.sort_unstable();
+        let mut h = 0u128;
+        for (i, a) in a_s.iter().enumerate() {
+            h += a << (7 * (i + (Amber.id() * ROOM_SIZE) as usize));
+        }
+        for (i, b) in b_s.iter().enumerate() {
+            h += b << (7 * (i + (Bronze.id() * ROOM_SIZE) as usize));
+        }
+        for (i, c) in c_s.iter().enumerate() {
+            h += c << (7 * (i + (Copper.id() * ROOM_SIZE) as usize));
+        }
+        for (i, d) in d_s.iter().enumerate() {
+            h += d << (7 * (i + (Desert.id() * ROOM_SIZE) as usize));
+        }
+        h
+    }
+
+    fn is_complete(&self) -> bool {
+        self.amphipods.iter().all(|it| it.is_home())
+    }
+
+    // A lower bound on the remaining cost: every amphipod outside its room must at least climb
+    // out to the hallway, walk to its room's door and step in.
+    fn min_remaining_cost(&self) -> u64 {
+        self.amphipods
+            .iter()
+            .filter(|it| !it.is_home())
+            .map(|it| {
+                let (column, climb) = match it.pos {
+                    Hallway(n) => (n, 0),
+                    RoomA(depth) => (2, ROOM_SIZE - depth),
+                    RoomB(depth) => (4, ROOM_SIZE - depth),
+                    RoomC(depth) => (6, ROOM_SIZE - depth),
+                    RoomD(depth) => (8, ROOM_SIZE - depth),
+                };
+                let door = match it.t {
+                    Amber => 2,
+                    Bronze => 4,
+                    Copper => 6,
+                    Desert => 8,
+                };
+                (climb + column.abs_diff(door) + 1) as u64 * it.cost() as u64
+            })
+            .sum()
+    }
+
+    fn is_settled(&self, amphipod: &Amphipod) -> bool {
+        amphipod.is_home()
+            && (0..amphipod.pos.index()).all(|depth| {
+                amphipod
+                    .home(depth)
+                    .occupant(&self.amphipods)
+                    .map(|it| it.is_home())
+                    .unwrap_or_default()
+            })
+    }
+
+    // Whether the hallway is empty between `from` (exclusive) and `to` (inclusive).
+    fn hallway_clear(&self, from: u8, to: u8) -> bool {
+        (from.min(to)..=from.max(to))
+            .filter(|it| *it != from)
+            .all(|it| !Hallway(it).occupied(&self.amphipods))
+    }
+
+    // Amphipods only ever stop in the hallway or in their own room, so each move takes an
+    // amphipod out of a room to a hallway spot, or from the hallway straight into its room.
+    fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = vec![];
+        for amphipod in self.amphipods.iter() {
+            let to = |pos: Position, steps: u8| Move {
+                source: *amphipod,
+                dest: Amphipod { pos, ..*amphipod },
+                score: steps as u32 * amphipod.cost(),
+            };
+            match amphipod.pos {
+                Hallway(column) => {
+                    if !amphipod.home_unoccupied_or_match(&self.amphipods)
+                        || !self.hallway_clear(column, amphipod.door())
+                    {
+                        continue;
+                    }
+                    let depth = (0..ROOM_SIZE)
+                        .find(|it| !amphipod.home(*it).occupied(&self.amphipods))
+                        .unwrap();
+                    let steps = column.abs_diff(amphipod.door()) + ROOM_SIZE - depth;
+                    // Going home is never worse than any other move, so take it straight away.
+                    return vec![to(amphipod.home(depth), steps)];
+                }
+                room => {
+                    let door = room.door().unwrap();
+                    let blocked = (room.index() + 1..ROOM_SIZE)
+                        .any(|depth| room.at_depth(depth).occupied(&self.amphipods));
+                    if blocked || self.is_settled(amphipod) {
+                        continue;
+                    }
+                    for column in (0..=10).filter(|it| !Hallway(*it).is_in_door()) {
+                        if self.hallway_clear(door, column) {
+                            let steps = ROOM_SIZE - room.index() + column.abs_diff(door);
+                            moves.push(to(Hallway(column), steps));
+                        }
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    fn apply_move(&self, mv: Move) -> State {
+        State {
+            amphipods: self
+                .amphipods
+                .iter()
+                .map(|it| if it.id == mv.source.id { mv.dest } else { *it })
+                .collect(),
+        }
+    }
+}
+
+impl SearchState for State {
+    type Key = u128;
+    type Move = Move;
+
+    fn key(&self) -> u128 {
+        self.pos_hash()
+    }
+
+    fn successors(&self) -> Vec<(Move, State, u64)> {
+        self.legal_moves()
+            .into_iter()
+            .map(|mv| (mv, self.apply_move(mv), mv.score as u64))
+            .collect()
+    }
+
+    fn is_goal(&self) -> bool {
+        self.is_complete()
+    }
+}
+
+// Part 2 unfolds the burrow by inserting these rows between the two rows of the diagram.
+const UNFOLDED_ROWS: [[char; 4]; 2] = [['D', 'C', 'B', 'A'], ['D', 'B', 'A', 'C']];
+
+const ROOMS: [fn(u8) -> Position; 4] = [RoomA, RoomB, RoomC, RoomD];
+
+fn initial_state(burrow: &Burrow) -> State {
+    let [top, bottom] = burrow.rows;
+    let [inserted_top, inserted_bottom] = UNFOLDED_ROWS;
+    let mut amphipods = vec![];
+    for layers in [
+        [(bottom, 0), (top, ROOM_SIZE_M1)],
+        [(inserted_bottom, 1), (inserted_top, 2)],
+    ] {
+        for (room, pos) in ROOMS.iter().enumerate() {
+            for (row, depth) in layers {
+                amphipods.push(Amphipod::new(
+                    amphipods.len() as AmphipodID,
+                    AmphipodType::from_char(row[room]),
+                    pos(depth),
+                ));
+            }
+        }
+    }
+    State { amphipods }
+}
+
+pub(super) fn best_score(burrow: &Burrow) -> u32 {
+    search::astar(initial_state(burrow), State::min_remaining_cost)
+        .unwrap()
+        .cost as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::day_23::Day23;
+    use crate::{examples, Solution};
+
+    #[test]
+    fn test_search_agrees() {
+        let burrow = Day23.parse(&examples::input(23, "example")).unwrap();
+        let guided = search::astar(initial_state(&burrow), State::min_remaining_cost).unwrap();
+        let cheapest = search::dijkstra(initial_state(&burrow)).unwrap();
+        assert_eq!((guided.cost, cheapest.cost), (44169, 44169));
+        assert!(guided.expanded <= cheapest.expanded);
+    }
+}
@@ -0,0 +1,587 @@
+use crate::parse::{self, Field, ParseError};
+use crate::{InputError, Line, Solution};
+use rayon::prelude::*;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Reg {
+    X,
+    Y,
+    Z,
+    W,
+}
+use Reg::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RegError;
+
+impl FromStr for Reg {
+    type Err = RegError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "x" => Ok(X),
+            "y" => Ok(Y),
+            "z" => Ok(Z),
+            "w" => Ok(W),
+            _ => Err(RegError),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operand {
+    Literal(i64),
+    Var(Reg),
+}
+
+use Operand::*;
+
+impl Operand {
+    fn parse(field: &Field) -> Result<Operand, ParseError> {
+        match field.text.parse::<Reg>() {
+            Ok(reg) => Ok(Var(reg)),
+            Err(_) => field.parse().map(Literal),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operation {
+    Inp(Operand),
+    Add(Operand, Operand),
+    Mul(Operand, Operand),
+    Div(Operand, Operand),
+    Mod(Operand, Operand),
+    Eql(Operand, Operand),
+}
+
+use Operation::*;
+
+impl Operation {
+    fn apply(self, reg: &mut Registers, digits: &[i64], i: &mut usize) {
+        match self {
+            Inp(Var(r)) => {
+                *i += 1;
+                reg.wr(r, digits[*i - 1])
+            }
+            Add(Var(a), b) => reg.wr(a, reg.rd(a) + reg.val(b)),
+            Mul(Var(a), b) => reg.wr(a, reg.rd(a) * reg.val(b)),
+            Div(Var(a), b) => reg.wr(a, reg.rd(a) / reg.val(b)),
+            Mod(Var(a), b) => reg.wr(a, reg.rd(a) % reg.val(b)),
+            Eql(Var(a), b) => reg.wr(a, if reg.rd(a) == reg.val(b) { 1 } else { 0 }),
+            _ => panic!("Invalid operation"),
+        }
+    }
+}
+
+impl Operation {
+    fn parse(line: &Field) -> Result<Operation, ParseError> {
+        let line = line.trim();
+        let name = line.split(" ")[0];
+        let arity = match name.text {
+            "inp" => 2,
+            "add" | "mul" | "div" | "mod" | "eql" => 3,
+            other => return Err(name.error(format!("unknown operation {:?}", other))),
+        };
+        let parts = line.record(" ", arity)?;
+        let target = parts[1]
+            .text
+            .parse::<Reg>()
+            .map(Var)
+            .map_err(|_| parts[1].error("expected a register"))?;
+        if arity == 2 {
+            return Ok(Inp(target));
+        }
+        let operand = Operand::parse(&parts[2])?;
+        Ok(match name.text {
+            "add" => Add(target, operand),
+            "mul" => Mul(target, operand),
+            "div" => Div(target, operand),
+            "mod" => Mod(target, operand),
+            _ => Eql(target, operand),
+        })
+    }
+}
+
+fn parse_program(input: &str) -> Result<Vec<Operation>, ParseError> {
+    parse::lines(input)
+        .iter()
+        .filter(|it| !it.is_empty())
+        .map(Operation::parse)
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Registers {
+    x: i64,
+    y: i64,
+    z: i64,
+    w: i64,
+}
+
+impl Registers {
+    fn wr(&mut self, reg: Reg, value: i64) {
+        match reg {
+            X => {
+                self.x = value;
+            }
+            Y => {
+                self.y = value;
+            }
+            Z => {
+                self.z = value;
+            }
+            W => {
+                self.w = value;
+            }
+        }
+    }
+    fn rd(&self, reg: Reg) -> i64 {
+        match reg {
+            X => self.x,
+            Y => self.y,
+            Z => self.z,
+            W => self.w,
+        }
+    }
+    fn val(&self, oprd: Operand) -> i64 {
+        match oprd {
+            Var(r) => self.rd(r),
+            Literal(n) => n,
+        }
+    }
+}
+
+fn is_valid(digits: &[i64], monad: &[Operation]) -> bool {
+    let mut reg = Registers::default();
+    let mut input_counter: usize = 0;
+    for op in monad {
+        op.apply(&mut reg, digits, &mut input_counter);
+    }
+    reg.z == 0
+}
+
+const DIGITS: usize = 14;
+const BLOCK_LEN: usize = 18;
+
+// The instructions MONAD runs for each digit; only `a`, `zdiv` and `b` differ between inputs.
+fn monad_block(a: i64, zdiv: i64, b: i64) -> [Operation; BLOCK_LEN] {
+    [
+        Inp(Var(W)),
+        Mul(Var(X), Literal(0)),
+        Add(Var(X), Var(Z)),
+        Mod(Var(X), Literal(26)),
+        Div(Var(Z), Literal(zdiv)),
+        Add(Var(X), Literal(a)),
+        Eql(Var(X), Var(W)),
+        Eql(Var(X), Literal(0)),
+        Mul(Var(Y), Literal(0)),
+        Add(Var(Y), Literal(25)),
+        Mul(Var(Y), Var(X)),
+        Add(Var(Y), Literal(1)),
+        Mul(Var(Z), Var(Y)),
+        Mul(Var(Y), Literal(0)),
+        Add(Var(Y), Var(W)),
+        Add(Var(Y), Literal(b)),
+        Mul(Var(Y), Var(X)),
+        Add(Var(Z), Var(Y)),
+    ]
+}
+
+pub struct Monad {
+    program: Vec<Operation>,
+    constants: Vec<(i64, i64, i64)>,
+}
+
+fn malformed(message: String) -> InputError {
+    InputError::Malformed { message }
+}
+
+impl Monad {
+    // Recovers each digit's `(a, zdiv, b)` and checks that a valid serial number exists.
+    fn new(program: Vec<Operation>) -> Result<Monad, InputError> {
+        if program.len() != DIGITS * BLOCK_LEN {
+            return Err(malformed(format!(
+                "expected {} instructions for {} digits, found {}",
+                DIGITS * BLOCK_LEN,
+                DIGITS,
+                program.len()
+            )));
+        }
+        let literal = |op: &Operation| match op {
+            Add(_, Literal(n)) | Div(_, Literal(n)) => Some(*n),
+            _ => None,
+        };
+        let mut constants = vec![];
+        for (digit, block) in program.chunks(BLOCK_LEN).enumerate() {
+            let found = literal(&block[5])
+                .zip(literal(&block[4]))
+                .zip(literal(&block[15]))
+                .map(|((a, zdiv), b)| (a, zdiv, b))
+                .filter(|(a, zdiv, b)| {
+                    block == monad_block(*a, *zdiv, *b) && [1, 26].contains(zdiv)
+                });
+            constants.push(found.ok_or_else(|| {
+                malformed(format!(
+                    "instructions {}..={} for digit {} do not follow the MONAD pattern",
+                    digit * BLOCK_LEN + 1,
+                    (digit + 1) * BLOCK_LEN,
+                    digit + 1
+                ))
+            })?);
+        }
+        let mut stack = vec![];
+        for (i, (a, zdiv, b)) in constants.iter().enumerate() {
+            if *zdiv == 1 {
+                stack.push((i, *b));
+                continue;
+            }
+            match stack.pop() {
+                Some((_, b_j)) if (b_j + a).abs() <= 8 => {}
+                Some((j, _)) => {
+                    return Err(malformed(format!(
+                        "no digits {} and {} satisfy the check",
+                        j + 1,
+                        i + 1
+                    )))
+                }
+                None => return Err(malformed(format!("digit {} has nothing to check", i + 1))),
+            }
+        }
+        if !stack.is_empty() {
+            return Err(malformed("some digits are never checked".to_string()));
+        }
+        Ok(Monad { program, constants })
+    }
+}
+
+#[cfg(test)]
+#[inline(always)]
+fn simple(z: i64, inp: i64, b: i64) -> i64 {
+    26 * z + inp + b
+}
+
+#[cfg(test)]
+#[inline(always)]
+fn complex(z: i64, inp: i64, a: i64, b: i64) -> i64 {
+    let x = ((z % 26) + a) != inp;
+    if x {
+        simple(z / 26, inp, b)
+    } else {
+        z / 26
+    }
+}
+
+// Hand-compiled from the constants of our input; `validate` is the general form.
+#[cfg(test)]
+fn validate_opt_opt(input: &[i64]) -> bool {
+    let mut z = simple(0, input[0], 8);
+    z = simple(z, input[1], 11);
+    z = simple(z, input[2], 2);
+    z = complex(z, input[3], -10, 11);
+    z = simple(z, input[4], 1);
+    z = complex(z, input[5], -3, 5);
+    z = complex(z, input[6], -14, 10);
+    z = simple(z, input[7], 6);
+    z = simple(z, input[8], 1);
+    z = simple(z, input[9], 11);
+    z = complex(z, input[10], -6, 9);
+    z = complex(z, input[11], -6, 14);
+    z = complex(z, input[12], -2, 11);
+    // The last step only leaves z at zero if z is exactly the digit it pops.
+    z == input[13] + 9
+}
+
+#[cfg(test)]
+fn validate_opt(input: &[i64]) -> bool {
+    let mut z = simple(0, input[0], 8);
+    z = simple(z, input[1], 11);
+    z = simple(z, input[2], 2);
+    z = complex(z, input[3], -10, 11);
+    z = simple(z, input[4], 1);
+    z = complex(z, input[5], -3, 5);
+    z = complex(z, input[6], -14, 10);
+    z = simple(z, input[7], 6);
+    z = simple(z, input[8], 1);
+    z = simple(z, input[9], 11);
+    z = complex(z, input[10], -6, 9);
+    z = complex(z, input[11], -6, 14);
+    z = complex(z, input[12], -2, 11);
+    z = complex(z, input[13], -9, 2);
+
+    z == 0
+}
+
+#[inline(always)]
+fn iter(mut z: i64, input: i64, a: i64, zdiv: i64, b: i64) -> i64 {
+    let x = if ((z % 26) + a) == input { 0 } else { 1 };
+    z /= zdiv;
+    z *= 1 + (25 * x);
+    z += x * (input + b);
+    z
+}
+
+fn validate(input: &[i64], constants: &[(i64, i64, i64)]) -> bool {
+    let mut z = 0;
+    for (inp, (a, zdiv, b)) in input.iter().zip(constants) {
+        z = iter(z, *inp, *a, *zdiv, *b);
+    }
+    z == 0
+}
+
+// Each `zdiv == 1` step pushes `input + b` onto the base-26 stack held in z and each `zdiv == 26`
+// step pops it, so z only returns to zero if every popped digit equals the pushed one plus `a`.
+fn solve_serial(monad: &Monad, largest: bool) -> [i64; DIGITS] {
+    let mut digits = [0; DIGITS];
+    let mut stack: Vec<(usize, i64)> = vec![];
+    for (i, (a, zdiv, b)) in monad.constants.iter().enumerate() {
+        if *zdiv == 1 {
+            stack.push((i, *b));
+            continue;
+        }
+        let (j, b_j) = stack.pop().unwrap();
+        let diff = b_j + a;
+        let (d_j, d_i) = match (largest, diff >= 0) {
+            (true, true) => (9 - diff, 9),
+            (true, false) => (9, 9 + diff),
+            (false, true) => (1, 1 + diff),
+            (false, false) => (1 - diff, 1),
+        };
+        digits[j] = d_j;
+        digits[i] = d_i;
+    }
+    debug_assert!(validate(&digits, &monad.constants) && is_valid(&digits, &monad.program));
+    digits
+}
+
+// The search `main` used to run: count through serial numbers from `start`, one place at a time
+// from the last, and test each of them. The sixth and seventh digits are searched in parallel.
+// Every serial number a task has still to test is worse than the one it is at, so it stops once
+// another task has found a better one. Far too slow for most inputs, but `solve_serial` depends on
+// the structure of MONAD and this does not.
+pub fn search_serial(monad: &Monad, start: [i64; DIGITS], largest: bool) -> Option<[i64; DIGITS]> {
+    const PAR_IDX: usize = 5;
+    const PAR_IDX_2: usize = 6;
+    let (first, step, last) = if largest { (9, -1, 1) } else { (1, 1, 9) };
+    let better = |a: &[i64], b: &[i64]| if largest { a > b } else { a < b };
+    let found: Mutex<Option<[i64; DIGITS]>> = Mutex::new(None);
+    // In search order, so the most promising tasks start first.
+    let order = move || (0..9).map(move |it| first + it * step);
+    let pairs: Vec<(i64, i64)> = order().flat_map(|a| order().map(move |b| (a, b))).collect();
+    pairs.par_iter().for_each(|(d, d_2)| {
+        let mut digits = start;
+        digits[PAR_IDX] = *d;
+        digits[PAR_IDX_2] = *d_2;
+        loop {
+            if validate(&digits, &monad.constants) {
+                let mut found = found.lock().unwrap();
+                if found.is_none_or(|it| better(&digits, &it)) {
+                    *found = Some(digits);
+                }
+                return;
+            }
+            let Some(idx) = (0..DIGITS)
+                .rev()
+                .filter(|it| *it != PAR_IDX && *it != PAR_IDX_2)
+                .find(|it| digits[*it] != last)
+            else {
+                return;
+            };
+            digits[idx] += step;
+            for digit in &mut digits[idx + 1..] {
+                *digit = first;
+            }
+            (digits[PAR_IDX], digits[PAR_IDX_2]) = (*d, *d_2);
+            // Checked once per carry, so the lock is rarely taken.
+            if idx < DIGITS - 1 && found.lock().unwrap().is_some_and(|it| better(&it, &digits)) {
+                return;
+            }
+        }
+    });
+    let found = found.into_inner().unwrap();
+    debug_assert!(found.is_none_or(|it| is_valid(&it, &monad.program)));
+    found
+}
+
+fn serial_number(digits: &[i64]) -> u64 {
+    digits.iter().fold(0, |acc, d| acc * 10 + *d as u64)
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u16 = 24;
+    type Input = Monad;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, InputError> {
+        Monad::new(parse_program(input)?)
+    }
+
+    fn parse_lines(
+        &self,
+        lines: &mut dyn Iterator<Item = Result<Line, InputError>>,
+    ) -> Result<Self::Input, InputError> {
+        let mut program = vec![];
+        for line in lines {
+            let line = line?;
+            if !line.field().is_empty() {
+                program.push(Operation::parse(&line.field())?);
+            }
+        }
+        Monad::new(program)
+    }
+
+    fn part1(&self, monad: &Monad) -> u64 {
+        serial_number(&solve_serial(monad, true))
+    }
+
+    fn part2(&self, monad: &Monad) -> u64 {
+        serial_number(&solve_serial(monad, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::differential::{shrink_vec, Differential, Rng};
+
+    fn monad(constants: [(i64, i64, i64); DIGITS]) -> Monad {
+        Monad::new(
+            constants
+                .iter()
+                .flat_map(|(a, zdiv, b)| monad_block(*a, *zdiv, *b))
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    // The constants `validate_opt` and `validate_opt_opt` were compiled from. Pushing blocks never
+    // match their digit, so their `a` only has to be more than 9.
+    fn our_monad() -> Monad {
+        monad([
+            (12, 1, 8),
+            (12, 1, 11),
+            (12, 1, 2),
+            (-10, 26, 11),
+            (12, 1, 1),
+            (-3, 26, 5),
+            (-14, 26, 10),
+            (12, 1, 6),
+            (12, 1, 1),
+            (12, 1, 11),
+            (-6, 26, 9),
+            (-6, 26, 14),
+            (-2, 26, 11),
+            (-9, 26, 2),
+        ])
+    }
+
+    // Five pairs of equal digits, then two nested pairs that differ, so only the last few digits
+    // have to be searched.
+    fn small_monad() -> Monad {
+        let same = [(12, 1, 4), (-4, 26, 7)];
+        let mut constants = [same; 5].concat();
+        constants.extend([(12, 1, 3), (12, 1, 0), (1, 26, 5), (-7, 26, 2)]);
+        monad(constants.try_into().unwrap())
+    }
+
+    // A valid serial number, with one digit changed half of the time.
+    fn random_serial(rng: &mut Rng, monad: &Monad) -> Vec<i64> {
+        let mut digits = vec![0; DIGITS];
+        let mut stack = vec![];
+        for (i, (a, zdiv, b)) in monad.constants.iter().enumerate() {
+            if *zdiv == 1 {
+                stack.push((i, *b));
+                continue;
+            }
+            let (j, b_j) = stack.pop().unwrap();
+            let diff = b_j + a;
+            digits[j] = rng.range(1.max(1 - diff)..=9.min(9 - diff));
+            digits[i] = digits[j] + diff;
+        }
+        if rng.range(0..=1) == 1 {
+            digits[rng.range(0..=DIGITS as i64 - 1) as usize] = rng.range(1..=9);
+        }
+        digits
+    }
+
+    #[test]
+    fn test_solve_serial() {
+        let monad = our_monad();
+        let largest = solve_serial(&monad, true);
+        assert_eq!(largest, [9, 9, 9, 1, 9, 7, 6, 5, 9, 4, 9, 4, 9, 8]);
+        assert!(validate_opt_opt(&largest));
+        let smallest = solve_serial(&monad, false);
+        assert_eq!(smallest, [2, 4, 9, 1, 3, 1, 1, 1, 6, 1, 6, 1, 5, 1]);
+        assert!(validate_opt(&smallest));
+
+        let monad = small_monad();
+        for (largest, start) in [(true, [9; DIGITS]), (false, [1; DIGITS])] {
+            let found = search_serial(&monad, start, largest).unwrap();
+            assert_eq!(found, solve_serial(&monad, largest));
+        }
+        assert_eq!(serial_number(&solve_serial(&monad, true)), 99999999999895);
+        assert_eq!(serial_number(&solve_serial(&monad, false)), 11111111115121);
+    }
+
+    #[test]
+    fn test_monad_pattern() {
+        // Each of the first seven digits is checked by one of the last seven, which must be the
+        // first digit plus `4 + a`.
+        let pattern = |a| -> Vec<Operation> {
+            (0..DIGITS)
+                .flat_map(|digit| {
+                    if digit < DIGITS / 2 {
+                        monad_block(12, 1, 4)
+                    } else {
+                        monad_block(a, 26, 1)
+                    }
+                })
+                .collect()
+        };
+        // The search agrees with the solver wherever it is quick enough to run.
+        let search = |a, largest| {
+            let monad = Monad::new(pattern(a)).unwrap();
+            let start = if largest { [9; DIGITS] } else { [1; DIGITS] };
+            let found = search_serial(&monad, start, largest).unwrap();
+            assert_eq!(found, solve_serial(&monad, largest));
+            serial_number(&found)
+        };
+        assert_eq!(search(-4, false), 11111111111111);
+        assert_eq!(search(-5, true), 99999998888888);
+        assert_eq!(search(-3, false), 11111112222222);
+        let mut program = pattern(-4);
+        program[BLOCK_LEN + 6] = Eql(Var(X), Var(Y));
+        assert!(Monad::new(program.clone()).is_err());
+        program.truncate(BLOCK_LEN);
+        assert!(Monad::new(program).is_err());
+    }
+
+    #[test]
+    fn test_parse_program() {
+        let program = parse_program("inp w\nadd z w\nmod z 2\n").unwrap();
+        assert!(is_valid(&[4], &program));
+        assert!(!is_valid(&[3], &program));
+        let err = parse_program("inp w\nadd z q1\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 7));
+        assert!(parse_program("nop x").is_err());
+    }
+
+    #[test]
+    fn test_validators_agree() {
+        let monad = our_monad();
+        let result = Differential::new()
+            .implementation("is_valid", |it: &Vec<i64>| is_valid(it, &monad.program))
+            .implementation("validate", |it: &Vec<i64>| validate(it, &monad.constants))
+            .implementation("validate_opt", |it: &Vec<i64>| validate_opt(it))
+            .implementation("validate_opt_opt", |it: &Vec<i64>| validate_opt_opt(it))
+            .shrink(|it: &Vec<i64>| shrink_vec(it, DIGITS, |d| (1..*d).rev().collect()))
+            .run(24, 2000, |rng| random_serial(rng, &monad));
+        if let Err(found) = result {
+            panic!("{}", found);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use crate::Solver;
+
+pub mod day_10;
+pub mod day_12;
+pub mod day_14;
+pub mod day_16;
+pub mod day_17;
+pub mod day_18;
+pub mod day_21;
+pub mod day_23;
+mod day_23_part_2;
+pub mod day_24;
+
+pub static SOLUTIONS: [&dyn Solver; 9] = [
+    &day_10::Day10,
+    &day_12::Day12,
+    &day_14::Day14,
+    &day_16::Day16,
+    &day_17::Day17,
+    &day_18::Day18,
+    &day_21::Day21,
+    &day_23::Day23,
+    &day_24::Day24,
+];
+
+pub fn find(day: u16) -> Option<&'static dyn Solver> {
+    SOLUTIONS.iter().find(|it| it.day() == day).copied()
+}
@@ -0,0 +1,207 @@
+use std::fmt::{self, Debug, Display, Formatter};
+use std::ops::RangeInclusive;
+
+/// SplitMix64, so a failing run can be replayed from its seed without pulling in a crate.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let span = (range.end() - range.start()) as u64 + 1;
+        range.start() + (self.next_u64() % span) as i64
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.next_u64() as usize % items.len()]
+    }
+}
+
+/// An input on which the implementations gave different outputs, after shrinking.
+#[derive(Clone, Debug)]
+pub struct Disagreement<I, O> {
+    pub seed: u64,
+    pub case: usize,
+    pub input: I,
+    pub outputs: Vec<(&'static str, O)>,
+    /// How many smaller disagreeing inputs were found on the way to `input`.
+    pub shrinks: usize,
+}
+
+impl<I: Debug, O: Debug> Display for Disagreement<I, O> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        writeln!(
+            f,
+            "case {} of seed {} disagrees (shrunk {} times) on {:?}",
+            self.case, self.seed, self.shrinks, self.input
+        )?;
+        for (name, output) in &self.outputs {
+            writeln!(f, "  {}: {:?}", name, output)?;
+        }
+        Ok(())
+    }
+}
+
+type Implementation<'a, I, O> = (&'static str, Box<dyn Fn(&I) -> O + 'a>);
+type Shrinker<'a, I> = Box<dyn Fn(&I) -> Vec<I> + 'a>;
+
+/// Runs several implementations of one computation on generated inputs and checks they agree.
+/// When they don't, the input is shrunk while they still disagree, so the case reported is small.
+pub struct Differential<'a, I, O> {
+    implementations: Vec<Implementation<'a, I, O>>,
+    shrink: Shrinker<'a, I>,
+}
+
+impl<'a, I: Clone, O: PartialEq> Differential<'a, I, O> {
+    pub fn new() -> Differential<'a, I, O> {
+        Differential {
+            implementations: vec![],
+            shrink: Box::new(|_| vec![]),
+        }
+    }
+
+    pub fn implementation(mut self, name: &'static str, f: impl Fn(&I) -> O + 'a) -> Self {
+        self.implementations.push((name, Box::new(f)));
+        self
+    }
+
+    /// Candidate inputs smaller than the given one, tried in order when shrinking.
+    pub fn shrink(mut self, f: impl Fn(&I) -> Vec<I> + 'a) -> Self {
+        self.shrink = Box::new(f);
+        self
+    }
+
+    // Every implementation's output, if any two differ.
+    fn disagreement(&self, input: &I) -> Option<Vec<(&'static str, O)>> {
+        let outputs: Vec<_> = self
+            .implementations
+            .iter()
+            .map(|(name, f)| (*name, f(input)))
+            .collect();
+        if outputs.windows(2).all(|it| it[0].1 == it[1].1) {
+            None
+        } else {
+            Some(outputs)
+        }
+    }
+
+    /// Checks `cases` inputs from `generate`, returning the first disagreement found.
+    pub fn run(
+        &self,
+        seed: u64,
+        cases: usize,
+        generate: impl Fn(&mut Rng) -> I,
+    ) -> Result<(), Disagreement<I, O>> {
+        let mut rng = Rng::new(seed);
+        for case in 0..cases {
+            let mut input = generate(&mut rng);
+            let Some(mut outputs) = self.disagreement(&input) else {
+                continue;
+            };
+            let mut shrinks = 0;
+            'shrinking: loop {
+                for candidate in (self.shrink)(&input) {
+                    if let Some(found) = self.disagreement(&candidate) {
+                        input = candidate;
+                        outputs = found;
+                        shrinks += 1;
+                        continue 'shrinking;
+                    }
+                }
+                break;
+            }
+            return Err(Disagreement {
+                seed,
+                case,
+                input,
+                outputs,
+                shrinks,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<'a, I: Clone, O: PartialEq> Default for Differential<'a, I, O> {
+    fn default() -> Differential<'a, I, O> {
+        Self::new()
+    }
+}
+
+/// Shrink candidates for a list: each single element removed, then each element shrunk by `item`,
+/// keeping at least `min_len` elements.
+pub fn shrink_vec<T: Clone>(
+    items: &[T],
+    min_len: usize,
+    item: impl Fn(&T) -> Vec<T>,
+) -> Vec<Vec<T>> {
+    let mut candidates = vec![];
+    if items.len() > min_len {
+        for i in 0..items.len() {
+            let mut smaller = items.to_vec();
+            smaller.remove(i);
+            candidates.push(smaller);
+        }
+    }
+    for (i, it) in items.iter().enumerate() {
+        for replacement in item(it) {
+            let mut smaller = items.to_vec();
+            smaller[i] = replacement;
+            candidates.push(smaller);
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum_loop(items: &Vec<i64>) -> i64 {
+        let mut total = 0;
+        for it in items {
+            total += it;
+        }
+        total
+    }
+
+    #[test]
+    fn test_differential() {
+        let generate = |rng: &mut Rng| -> Vec<i64> {
+            let len = rng.range(0..=12) as usize;
+            (0..len).map(|_| rng.range(-50..=50)).collect()
+        };
+        let agreeing = Differential::new()
+            .implementation("loop", sum_loop)
+            .implementation("iter", |it: &Vec<i64>| it.iter().sum::<i64>());
+        assert!(agreeing.run(1, 200, generate).is_ok());
+
+        // Ignoring negative numbers is wrong, and the smallest input showing it is a single -1.
+        let differing = Differential::new()
+            .implementation("loop", sum_loop)
+            .implementation("positive", |it: &Vec<i64>| {
+                it.iter().filter(|it| **it > 0).sum::<i64>()
+            })
+            .shrink(|it: &Vec<i64>| {
+                shrink_vec(it, 0, |n| match n {
+                    0 => vec![],
+                    n => vec![n / 2, n - n.signum()],
+                })
+            });
+        let found = differing.run(1, 200, generate).unwrap_err();
+        assert_eq!(found.input, [-1]);
+        assert_eq!(found.outputs, [("loop", -1), ("positive", 0)]);
+        assert!(found.to_string().contains("  positive: 0\n"));
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{InputError, InputOptions, InputSource, Part};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const EXAMPLES_DIR: &str = "examples";
+const EXPECTED_FILE: &str = "answers.txt";
+
+/// A named sample input from `<input dir>/examples/day_N_<name>.txt`. Expected answers live in
+/// `examples/answers.txt`, one `<day> <name> <part> <answer>` per line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Example {
+    pub day: u16,
+    pub name: String,
+    pub input: String,
+    pub expected: BTreeMap<Part, String>,
+}
+
+pub fn dir(opts: &InputOptions) -> PathBuf {
+    opts.input_dir().join(EXAMPLES_DIR)
+}
+
+fn file_name(day: u16, name: &str) -> String {
+    format!("day_{}_{}.txt", day, name)
+}
+
+fn expected_answers(dir: &Path) -> Result<BTreeMap<(u16, String, Part), String>, InputError> {
+    let contents = match InputSource::File(dir.join(EXPECTED_FILE)).read() {
+        Ok(contents) => contents,
+        Err(InputError::Missing { .. } | InputError::Empty { .. }) => return Ok(BTreeMap::new()),
+        Err(err) => return Err(err),
+    };
+    let mut expected = BTreeMap::new();
+    for (idx, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.splitn(4, ' ').collect();
+        let parsed = match fields[..] {
+            [day, name, part, answer] => day
+                .parse::<u16>()
+                .ok()
+                .zip(part.parse::<Part>().ok())
+                .map(|(day, part)| ((day, name.to_string(), part), answer.trim().to_string())),
+            _ => None,
+        };
+        let (key, answer) = parsed.ok_or_else(|| InputError::Malformed {
+            message: format!(
+                "example answers line {}: expected `<day> <name> <part> <answer>`",
+                idx + 1
+            ),
+        })?;
+        expected.insert(key, answer);
+    }
+    Ok(expected)
+}
+
+fn read_example(
+    dir: &Path,
+    day: u16,
+    name: &str,
+    expected: &BTreeMap<(u16, String, Part), String>,
+) -> Result<Example, InputError> {
+    let input = InputSource::File(dir.join(file_name(day, name))).read()?;
+    Ok(Example {
+        day,
+        name: name.to_string(),
+        input,
+        expected: [Part::One, Part::Two]
+            .into_iter()
+            .filter_map(|part| {
+                expected
+                    .get(&(day, name.to_string(), part))
+                    .map(|answer| (part, answer.clone()))
+            })
+            .collect(),
+    })
+}
+
+pub fn load(dir: &Path, day: u16, name: &str) -> Result<Example, InputError> {
+    read_example(dir, day, name, &expected_answers(dir)?)
+}
+
+/// Every example registered for `day`, sorted by name.
+pub fn for_day(dir: &Path, day: u16) -> Result<Vec<Example>, InputError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(source) => {
+            return Err(InputError::Unreadable {
+                path: dir.into(),
+                source,
+            })
+        }
+    };
+    let prefix = format!("day_{}_", day);
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|file| {
+            file.strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(".txt"))
+                .map(|name| name.to_string())
+        })
+        .collect();
+    names.sort();
+    let expected = expected_answers(dir)?;
+    names
+        .iter()
+        .map(|name| read_example(dir, day, name, &expected))
+        .collect()
+}
+
+/// Example input from the default location, for unit tests.
+pub fn input(day: u16, name: &str) -> String {
+    load(&dir(&InputOptions::default()), day, name)
+        .unwrap_or_else(|err| panic!("{}", err))
+        .input
+}
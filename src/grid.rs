@@ -0,0 +1,303 @@
+use crate::parse::{self, ParseError};
+use std::fmt::{self, Display, Formatter};
+
+/// An `(x, y)` position; `y` grows downwards. Signed so lookups may fall outside the grid.
+pub type Pos = (i64, i64);
+
+const ORTHOGONAL: [Pos; 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const SURROUNDING: [Pos; 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangular grid stored row by row. A wrapping grid treats its edges as joined; a grid with
+/// a background behaves as if it extends forever, filled with the background outside its bounds.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+    wrapping: bool,
+    background: Option<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |it| it.len());
+        assert!(
+            rows.iter().all(|it| it.len() == width),
+            "grid rows differ in length"
+        );
+        Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+            wrapping: false,
+            background: None,
+        }
+    }
+
+    pub fn parse(input: &str, cell: impl Fn(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        Ok(Self::from_rows(parse::grid(input, cell)?))
+    }
+
+    pub fn wrapping(mut self) -> Grid<T> {
+        self.wrapping = true;
+        self
+    }
+
+    pub fn with_background(mut self, background: T) -> Grid<T> {
+        self.background = Some(background);
+        self
+    }
+
+    pub fn set_background(&mut self, background: T) {
+        self.background = Some(background);
+    }
+
+    pub fn background(&self) -> Option<&T> {
+        self.background.as_ref()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    // The cell index for a position, after wrapping if enabled.
+    fn index(&self, (x, y): Pos) -> Option<usize> {
+        let (x, y) = if self.wrapping && !self.cells.is_empty() {
+            (
+                x.rem_euclid(self.width as i64),
+                y.rem_euclid(self.height as i64),
+            )
+        } else {
+            (x, y)
+        };
+        if self.contains((x, y)) {
+            Some(y as usize * self.width + x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        match self.index(pos) {
+            Some(idx) => Some(&self.cells[idx]),
+            None => self.background.as_ref(),
+        }
+    }
+
+    /// Mutable access to a stored cell; the background cannot be changed one cell at a time.
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index(pos).map(|idx| &mut self.cells[idx])
+    }
+
+    /// Stores `value` at `pos`, returning false if `pos` is not a stored cell.
+    pub fn set(&mut self, pos: Pos, value: T) -> bool {
+        match self.get_mut(pos) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x as i64, y as i64)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    fn neighbours(&self, pos: Pos, offsets: &'static [Pos]) -> impl Iterator<Item = Pos> + '_ {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let next = (pos.0 + dx, pos.1 + dy);
+            if self.background.is_some() {
+                Some(next)
+            } else {
+                self.index(next)
+                    .map(|idx| ((idx % self.width) as i64, (idx / self.width) as i64))
+            }
+        })
+    }
+
+    /// The up, left, right and down neighbours of `pos`.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &ORTHOGONAL)
+    }
+
+    /// All eight surrounding positions, row by row.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &SURROUNDING)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(&f).collect(),
+            wrapping: self.wrapping,
+            background: self.background.as_ref().map(&f),
+        }
+    }
+
+    /// A `width` by `height` window whose top-left corner is at `origin`. Positions outside the
+    /// grid read through to the background or wrap, as for `get`.
+    pub fn view(&self, origin: Pos, width: usize, height: usize) -> View<'_, T> {
+        View {
+            grid: self,
+            origin,
+            width,
+            height,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+            wrapping: false,
+            background: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct View<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Pos,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T> View<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Looks up a position relative to the view's origin.
+    pub fn get(&self, (x, y): Pos) -> Option<&'a T> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        self.grid.get((self.origin.0 + x, self.origin.1 + y))
+    }
+}
+
+impl<T: Display> Display for View<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        for y in 0..self.height as i64 {
+            for x in 0..self.width as i64 {
+                match self.get((x, y)) {
+                    Some(cell) => write!(f, "{}", cell)?,
+                    None => write!(f, " ")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.view((0, 0), self.width, self.height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(s: &str) -> Grid<u32> {
+        Grid::parse(s, |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789");
+        let values = |grid: &Grid<u32>, it: Vec<Pos>| -> Vec<u32> {
+            it.into_iter().map(|pos| *grid.get(pos).unwrap()).collect()
+        };
+        assert_eq!(values(&grid, grid.neighbours4((0, 0)).collect()), [2, 4]);
+        assert_eq!(
+            values(&grid, grid.neighbours8((1, 1)).collect()),
+            [1, 2, 3, 4, 6, 7, 8, 9]
+        );
+        let wrapped = grid.clone().wrapping();
+        assert_eq!(
+            values(&wrapped, wrapped.neighbours4((0, 0)).collect()),
+            [7, 3, 2, 4]
+        );
+        assert_eq!(wrapped.neighbours4((2, 2)).nth(2), Some((0, 2)));
+    }
+
+    #[test]
+    fn test_background_and_views() {
+        let mut grid = Grid::parse("#.\n.#", Some).unwrap().with_background('.');
+        assert_eq!(grid.get((-5, 7)), Some(&'.'));
+        assert_eq!(grid.neighbours8((0, 0)).count(), 8);
+        assert!(!grid.set((2, 0), '#'));
+        assert!(grid.set((1, 0), '#'));
+        assert_eq!(grid.to_string(), "##\n.#\n");
+        assert_eq!(grid.view((-1, 0), 4, 1).to_string(), ".##.\n");
+        assert_eq!(digits("123\n456").view((1, 1), 2, 1).to_string(), "56\n");
+        assert_eq!(digits("12").map(|it| it * 2).get((1, 0)), Some(&4));
+    }
+
+    #[test]
+    fn test_parse_puzzle_grids() {
+        // Trimmed-down versions of the grids the days read.
+        for input in [
+            "2199943210\n3987894921\n9856789892",
+            "5483\n2745\n5264\n6141",
+        ] {
+            let grid = digits(input);
+            assert_eq!(grid.height(), input.lines().count());
+            assert_eq!(grid.width(), input.lines().next().unwrap().len());
+        }
+        let day_20 = "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..##\n\n#..#.\n#....\n##..#";
+        let (_, image) = day_20.split_once("\n\n").unwrap();
+        let image = Grid::parse(image, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!((image.width(), image.height()), (5, 3));
+        assert_eq!(image.get((1, 2)), Some(&true));
+        let herd = Grid::parse("v...>>.vv>\n.vv>>.vv..\n>>.>v>...v", |c| {
+            "v>.".contains(c).then_some(c)
+        })
+        .unwrap();
+        let corner = (herd.width() as i64 - 1, herd.height() as i64 - 1);
+        assert_eq!(herd.clone().wrapping().get((-1, -1)), herd.get(corner));
+        assert_eq!(herd.get(corner), Some(&'v'));
+        assert!(Grid::parse("v>x", |c| "v>.".contains(c).then_some(c)).is_err());
+    }
+}
@@ -1,16 +1,559 @@
-use std::path::Path;
+use output::{Fingerprint, Format};
+use parse::Field;
+use std::fmt::{self, Display, Formatter};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
-pub fn load_input(day: u16) -> String {
-    let filename = Path::new("./inputs").join(&format!("day_{}.txt", day));
-    std::fs::read_to_string(filename)
-        .unwrap()
-        .trim()
-        .to_string()
+pub mod answers;
+pub mod bench;
+pub mod brackets;
+pub mod days;
+pub mod differential;
+pub mod examples;
+pub mod grid;
+pub mod memo;
+pub mod output;
+pub mod parse;
+pub mod report;
+pub mod search;
+pub mod trace;
+
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+pub const INPUT_FILE_VAR: &str = "AOC_INPUT";
+const DEFAULT_INPUT_DIR: &str = "./inputs";
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing {
+        path: PathBuf,
+    },
+    Unreadable {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    NotUtf8 {
+        path: PathBuf,
+    },
+    Empty {
+        path: PathBuf,
+    },
+    Unwritable {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Malformed {
+        message: String,
+    },
+    Usage {
+        message: String,
+    },
 }
 
-pub fn input_lines(day: u16) -> Vec<String> {
-    load_input(day)
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::Missing { path } => write!(f, "input file {} does not exist", path.display()),
+            Self::Unreadable { path, source } => {
+                write!(
+                    f,
+                    "could not read input file {}: {}",
+                    path.display(),
+                    source
+                )
+            }
+            Self::NotUtf8 { path } => {
+                write!(f, "input file {} is not valid UTF-8", path.display())
+            }
+            Self::Empty { path } => write!(f, "input file {} is empty", path.display()),
+            Self::Unwritable { path, source } => {
+                write!(f, "could not write {}: {}", path.display(), source)
+            }
+            Self::Malformed { message } => write!(f, "malformed input: {}", message),
+            Self::Usage { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Unreadable { source, .. } | Self::Unwritable { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    fn parse(s: &str, day: u16) -> InputSource {
+        if s == "-" {
+            Self::Stdin
+        } else {
+            Self::File(PathBuf::from(s.replace("{day}", &day.to_string())))
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        match self {
+            Self::File(path) => path.clone(),
+            Self::Stdin => PathBuf::from("<stdin>"),
+        }
+    }
+
+    pub fn read(&self) -> Result<String, InputError> {
+        let path = self.path();
+        let mut contents = String::new();
+        match self {
+            Self::File(path) => {
+                std::fs::File::open(path).and_then(|mut file| file.read_to_string(&mut contents))
+            }
+            Self::Stdin => std::io::stdin().read_to_string(&mut contents),
+        }
+        .map_err(|err| read_error(&path, err))?;
+        let trimmed = contents.trim();
+        if trimmed.is_empty() {
+            return Err(InputError::Empty { path });
+        }
+        Ok(trimmed.to_string())
+    }
+
+    /// Streams the input line by line instead of reading it all at once.
+    pub fn lines(&self) -> Result<Lines<Box<dyn BufRead>>, InputError> {
+        let path = self.path();
+        let reader: Box<dyn BufRead> = match self {
+            Self::File(file) => Box::new(BufReader::new(
+                std::fs::File::open(file).map_err(|err| read_error(&path, err))?,
+            )),
+            Self::Stdin => Box::new(std::io::stdin().lock()),
+        };
+        Ok(Lines::new(reader, path))
+    }
+}
+
+fn read_error(path: &Path, err: std::io::Error) -> InputError {
+    match err.kind() {
+        std::io::ErrorKind::NotFound => InputError::Missing { path: path.into() },
+        std::io::ErrorKind::InvalidData => InputError::NotUtf8 { path: path.into() },
+        _ => InputError::Unreadable {
+            path: path.into(),
+            source: err,
+        },
+    }
+}
+
+/// An owned line of input, numbered from 1.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Line {
+    pub number: usize,
+    pub text: String,
+}
+
+impl Line {
+    pub fn field(&self) -> Field<'_> {
+        Field::new(self.number, &self.text)
+    }
+}
+
+/// Reads lines from a `BufRead` one at a time, without line terminators. Like `InputSource::read`
+/// it fails with `InputError::Empty` if the input has no non-blank lines.
+pub struct Lines<R> {
+    reader: R,
+    path: PathBuf,
+    buffer: String,
+    number: usize,
+    fingerprint: Fingerprint,
+    seen_content: bool,
+    done: bool,
+}
+
+impl<R: BufRead> Lines<R> {
+    pub fn new(reader: R, path: PathBuf) -> Lines<R> {
+        Lines {
+            reader,
+            path,
+            buffer: String::new(),
+            number: 0,
+            fingerprint: Fingerprint::default(),
+            seen_content: false,
+            done: false,
+        }
+    }
+
+    /// The next line, borrowed from an internal buffer that is reused for every line.
+    pub fn next_field(&mut self) -> Option<Result<Field<'_>, InputError>> {
+        if self.done {
+            return None;
+        }
+        self.buffer.clear();
+        match self.reader.read_line(&mut self.buffer) {
+            Ok(0) => {
+                self.done = true;
+                if self.seen_content {
+                    None
+                } else {
+                    Some(Err(InputError::Empty {
+                        path: self.path.clone(),
+                    }))
+                }
+            }
+            Ok(_) => {
+                self.number += 1;
+                let text = self.buffer.trim_end_matches(['\n', '\r']);
+                self.fingerprint.add_line(text);
+                self.seen_content |= !text.trim().is_empty();
+                Some(Ok(Field::new(self.number, text)))
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(read_error(&self.path, err)))
+            }
+        }
+    }
+
+    /// Fingerprint of the lines read so far.
+    pub fn fingerprint(&self) -> Fingerprint {
+        self.fingerprint
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = Result<Line, InputError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_field().map(|field| {
+            field.map(|it| Line {
+                number: it.line,
+                text: it.text.to_string(),
+            })
+        })
+    }
+}
+
+/// Where to find puzzle inputs. `--input <file>` / `AOC_INPUT` pick a single file (or `-` for
+/// stdin) and take precedence over `--input-dir <dir>` / `AOC_INPUT_DIR`, which replaces
+/// `./inputs` as the directory holding `day_N.txt`. Any flag overrides both environment variables,
+/// and a `{day}` in a file path is replaced by the day number.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InputOptions {
+    pub dir: Option<PathBuf>,
+    pub file: Option<String>,
+}
+
+impl InputOptions {
+    /// Splits the input flags out of `args`, returning the options and the remaining arguments.
+    pub fn from_args<I: IntoIterator<Item = String>>(
+        args: I,
+    ) -> Result<(InputOptions, Vec<String>), InputError> {
+        let mut opts = InputOptions::default();
+        let mut rest = vec![];
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+            if flag != "--input" && flag != "--input-dir" {
+                rest.push(arg);
+                continue;
+            }
+            let value = inline_value
+                .or_else(|| args.next())
+                .ok_or_else(|| InputError::Usage {
+                    message: format!("{} requires a value", flag),
+                })?;
+            if flag == "--input" {
+                opts.file = Some(value);
+            } else {
+                opts.dir = Some(PathBuf::from(value));
+            }
+        }
+        Ok((opts, rest))
+    }
+
+    pub fn from_env() -> Result<(InputOptions, Vec<String>), InputError> {
+        let (opts, rest) = Self::from_args(std::env::args().skip(1))?;
+        if opts.file.is_some() || opts.dir.is_some() {
+            return Ok((opts, rest));
+        }
+        Ok((
+            InputOptions {
+                dir: std::env::var_os(INPUT_DIR_VAR).map(PathBuf::from),
+                file: std::env::var(INPUT_FILE_VAR).ok(),
+            },
+            rest,
+        ))
+    }
+
+    pub fn input_dir(&self) -> &Path {
+        self.dir
+            .as_deref()
+            .unwrap_or_else(|| Path::new(DEFAULT_INPUT_DIR))
+    }
+
+    pub fn source(&self, day: u16) -> InputSource {
+        match &self.file {
+            Some(file) => InputSource::parse(file, day),
+            None => InputSource::File(self.input_dir().join(format!("day_{}.txt", day))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn wanted(self, selection: Option<Part>) -> bool {
+        selection.is_none_or(|it| it == self)
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::One => write!(f, "1"),
+            Self::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = InputError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Self::One),
+            "2" => Ok(Self::Two),
+            _ => Err(InputError::Usage {
+                message: format!("unknown part {:?}, expected 1 or 2", s),
+            }),
+        }
+    }
+}
+
+pub fn try_load_input(day: u16) -> Result<String, InputError> {
+    InputOptions::from_env()?.0.source(day).read()
+}
+
+pub fn try_input_lines(day: u16) -> Result<Vec<String>, InputError> {
+    Ok(try_load_input(day)?
         .lines()
         .map(|it| it.trim().to_string())
-        .collect()
+        .collect())
+}
+
+pub fn load_input(day: u16) -> String {
+    try_load_input(day).unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub fn input_lines(day: u16) -> Vec<String> {
+    try_input_lines(day).unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub trait Solution {
+    const DAY: u16;
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn load(&self) -> Result<String, InputError> {
+        try_load_input(Self::DAY)
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input, InputError>;
+    /// Builds the input from streamed lines. The default collects them and calls `parse`, so
+    /// line-oriented days override it to avoid holding the whole text.
+    fn parse_lines(
+        &self,
+        lines: &mut dyn Iterator<Item = Result<Line, InputError>>,
+    ) -> Result<Self::Input, InputError> {
+        let mut input = String::new();
+        for line in lines {
+            input.push_str(&line?.text);
+            input.push('\n');
+        }
+        self.parse(input.trim())
+    }
+    fn part1(&self, input: &Self::Input) -> Self::Answer1;
+    fn part2(&self, input: &Self::Input) -> Self::Answer2;
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Answer {
+    pub part: Part,
+    pub value: String,
+    pub elapsed: Duration,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Run {
+    pub parse_time: Duration,
+    pub answers: Vec<Answer>,
+}
+
+/// Object-safe view of a `Solution`, so front-ends can hold every day in one table.
+pub trait Solver: Sync {
+    fn day(&self) -> u16;
+    fn load(&self) -> Result<String, InputError>;
+    fn solve(&self, input: &str, part: Option<Part>) -> Result<Run, InputError>;
+    fn solve_lines(
+        &self,
+        lines: &mut dyn Iterator<Item = Result<Line, InputError>>,
+        part: Option<Part>,
+    ) -> Result<Run, InputError>;
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+impl<S: Solution + Sync> Solver for S {
+    fn day(&self) -> u16 {
+        S::DAY
+    }
+
+    fn load(&self) -> Result<String, InputError> {
+        Solution::load(self)
+    }
+
+    fn solve(&self, input: &str, part: Option<Part>) -> Result<Run, InputError> {
+        let (parsed, parse_time) = timed(|| self.parse(input));
+        Ok(solve_parsed(self, &parsed?, parse_time, part))
+    }
+
+    fn solve_lines(
+        &self,
+        lines: &mut dyn Iterator<Item = Result<Line, InputError>>,
+        part: Option<Part>,
+    ) -> Result<Run, InputError> {
+        let (parsed, parse_time) = timed(|| self.parse_lines(lines));
+        Ok(solve_parsed(self, &parsed?, parse_time, part))
+    }
+}
+
+fn solve_parsed<S: Solution>(
+    solution: &S,
+    parsed: &S::Input,
+    parse_time: Duration,
+    part: Option<Part>,
+) -> Run {
+    let mut answers = vec![];
+    if Part::One.wanted(part) {
+        let (value, elapsed) = timed(|| solution.part1(parsed).to_string());
+        answers.push(Answer {
+            part: Part::One,
+            value,
+            elapsed,
+        });
+    }
+    if Part::Two.wanted(part) {
+        let (value, elapsed) = timed(|| solution.part2(parsed).to_string());
+        answers.push(Answer {
+            part: Part::Two,
+            value,
+            elapsed,
+        });
+    }
+    Run {
+        parse_time,
+        answers,
+    }
+}
+
+pub fn run_on(
+    solver: &dyn Solver,
+    input: &str,
+    part: Option<Part>,
+    format: Format,
+) -> Result<(), InputError> {
+    let run = solver.solve(input, part)?;
+    print_run(solver.day(), &run, output::fingerprint(input), format);
+    Ok(())
+}
+
+/// Like `run_on`, but streams the input from `source` line by line.
+pub fn run_streaming(
+    solver: &dyn Solver,
+    source: &InputSource,
+    part: Option<Part>,
+    format: Format,
+) -> Result<(), InputError> {
+    let mut lines = source.lines()?;
+    let run = solver.solve_lines(&mut lines, part)?;
+    print_run(solver.day(), &run, lines.fingerprint(), format);
+    Ok(())
+}
+
+fn print_run(day: u16, run: &Run, fingerprint: Fingerprint, format: Format) {
+    for line in output::lines(day, run, fingerprint, format) {
+        println!("{}", line);
+    }
+}
+
+/// Solves the day's own input, printing JSON lines if `--json` was passed.
+pub fn run(solver: &dyn Solver, part: Option<Part>) -> Result<(), InputError> {
+    let mut args = InputOptions::from_env()?.1;
+    let format = Format::from_args(&mut args);
+    trace::set_level(trace::Level::from_args(&mut args));
+    run_on(solver, &solver.load()?, part, format)
+}
+
+pub fn exit_on_error<T, E: Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|it| it.to_string()).collect()
+    }
+
+    #[test]
+    fn test_input_flags() {
+        let (opts, rest) = InputOptions::from_args(args("10 --input-dir scratch 2")).unwrap();
+        assert_eq!(rest, args("10 2"));
+        assert_eq!(
+            opts.source(10),
+            InputSource::File(PathBuf::from("scratch/day_10.txt"))
+        );
+
+        let (opts, _) = InputOptions::from_args(args("--input=mine/{day}.txt")).unwrap();
+        assert_eq!(
+            opts.source(4),
+            InputSource::File(PathBuf::from("mine/4.txt"))
+        );
+
+        let (opts, _) = InputOptions::from_args(args("--input - --input-dir x")).unwrap();
+        assert_eq!(opts.source(4), InputSource::Stdin);
+
+        assert!(InputOptions::from_args(args("--input")).is_err());
+    }
+
+    #[test]
+    fn test_streamed_lines() {
+        let mut lines = Lines::new("[<>]\r\n\n{}".as_bytes(), PathBuf::from("test"));
+        let first = lines.next_field().unwrap().unwrap();
+        assert_eq!((first.line, first.text), (1, "[<>]"));
+        let rest: Vec<Line> = lines.by_ref().map(|it| it.unwrap()).collect();
+        assert_eq!(rest.len(), 2);
+        assert_eq!(rest[1].field().line, 3);
+        assert_eq!(lines.fingerprint(), output::fingerprint("[<>]\n{}"));
+
+        let mut blank = Lines::new(" \n".as_bytes(), PathBuf::from("test"));
+        assert!(matches!(blank.nth(1), Some(Err(InputError::Empty { .. }))));
+    }
 }
@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub cycles: u64,
+    pub evictions: u64,
+}
+
+/// A cache for recursive solvers. `get_or_compute` hands the memo back to the computation so it
+/// can recurse, and reports a cycle instead of recursing forever when a key is requested again
+/// while it is still being computed. A value computed while a cycle was cut depends on where the
+/// computation started, so it is returned but not cached. With a capacity, the oldest entries are
+/// evicted first.
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    in_progress: HashSet<K>,
+    // Whether the computation under way has had a cycle cut anywhere below it.
+    cut: bool,
+    capacity: Option<usize>,
+    order: VecDeque<K>,
+    stats: MemoStats,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Memo<K, V> {
+        Memo {
+            cache: HashMap::new(),
+            in_progress: HashSet::new(),
+            cut: false,
+            capacity: None,
+            order: VecDeque::new(),
+            stats: MemoStats::default(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Memo<K, V> {
+        Memo {
+            capacity: Some(capacity),
+            ..Self::new()
+        }
+    }
+
+    /// The cached value for `key`, computing it if needed. Returns `None` if `key` is already
+    /// being computed further up the stack.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> Option<V> {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return Some(value.clone());
+        }
+        if !self.in_progress.insert(key.clone()) {
+            self.stats.cycles += 1;
+            self.cut = true;
+            return None;
+        }
+        self.stats.misses += 1;
+        let outer_cut = std::mem::take(&mut self.cut);
+        let value = compute(self);
+        self.in_progress.remove(&key);
+        if !self.cut {
+            self.insert(key, value.clone());
+        }
+        self.cut |= outer_cut;
+        Some(value)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if let Some(capacity) = self.capacity {
+            if capacity == 0 {
+                return;
+            }
+            if !self.cache.contains_key(&key) {
+                if self.cache.len() >= capacity {
+                    if let Some(oldest) = self.order.pop_front() {
+                        self.cache.remove(&oldest);
+                        self.stats.evictions += 1;
+                    }
+                }
+                self.order.push_back(key.clone());
+            }
+        }
+        self.cache.insert(key, value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for Memo<K, V> {
+    fn default() -> Memo<K, V> {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        if n < 2 {
+            return n;
+        }
+        memo.get_or_compute(n, |memo| fib(n - 1, memo) + fib(n - 2, memo))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(fib(90, &mut memo), 2880067194370816120);
+        let stats = memo.stats();
+        assert_eq!((stats.misses, stats.hits, stats.cycles), (89, 87, 0));
+
+        let mut bounded = Memo::with_capacity(3);
+        assert_eq!(fib(30, &mut bounded), 832040);
+        assert_eq!(bounded.len(), 3);
+        assert_eq!(bounded.stats().evictions, 26);
+
+        // A key that depends on itself is reported as a cycle rather than overflowing the stack.
+        let mut memo: Memo<u32, Option<u32>> = Memo::new();
+        let value = memo.get_or_compute(1, |memo| memo.get_or_compute(1, |_| Some(0)).flatten());
+        assert_eq!(value, Some(None));
+        assert_eq!(memo.stats().cycles, 1);
+        assert!(memo.is_empty());
+
+        // Counting the keys reachable from each key, with a cycle 1 -> 2 -> 1 beside an acyclic 3.
+        fn reachable(key: u32, memo: &mut Memo<u32, u32>) -> u32 {
+            let next: &[u32] = match key {
+                1 => &[2],
+                2 => &[1, 3],
+                _ => &[],
+            };
+            memo.get_or_compute(key, |memo| {
+                1 + next.iter().map(|it| reachable(*it, memo)).sum::<u32>()
+            })
+            .unwrap_or(0)
+        }
+        let mut memo = Memo::new();
+        assert_eq!(reachable(1, &mut memo), 3);
+        // Only 3 was computed without cutting the cycle, so 2 is not stuck at the count it got
+        // while 1 was in progress.
+        assert_eq!((memo.get(&2), memo.get(&3)), (None, Some(&1)));
+        assert_eq!(reachable(2, &mut memo), 3);
+        assert_eq!(memo.stats().hits, 1);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::Run;
+use std::fmt::{self, Display, Formatter};
+
+pub const JSON_FLAG: &str = "--json";
+
+/// How answers are printed: `Part N: answer` lines, or one JSON object per answer with the day,
+/// part, answer, elapsed nanoseconds and a fingerprint of the input it was computed from.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl Format {
+    /// Removes `--json` from `args`, selecting JSON output if it was there.
+    pub fn from_args(args: &mut Vec<String>) -> Format {
+        let before = args.len();
+        args.retain(|it| it != JSON_FLAG);
+        if args.len() < before {
+            Format::Json
+        } else {
+            Format::Text
+        }
+    }
+}
+
+/// FNV-1a hash of an input's non-blank lines, without trailing whitespace, so results can be
+/// matched to the input that produced them whether it was read whole or streamed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Fingerprint(u64);
+
+impl Default for Fingerprint {
+    fn default() -> Fingerprint {
+        Fingerprint(0xcbf29ce484222325)
+    }
+}
+
+impl Fingerprint {
+    pub fn add_line(&mut self, line: &str) {
+        let line = line.trim_end();
+        if line.is_empty() {
+            return;
+        }
+        for byte in line.bytes().chain(std::iter::once(b'\n')) {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+pub fn fingerprint(input: &str) -> Fingerprint {
+    let mut fingerprint = Fingerprint::default();
+    for line in input.lines() {
+        fingerprint.add_line(line);
+    }
+    fingerprint
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub fn lines(day: u16, run: &Run, fingerprint: Fingerprint, format: Format) -> Vec<String> {
+    run.answers
+        .iter()
+        .map(|answer| match format {
+            Format::Text => format!("Part {}: {}", answer.part, answer.value),
+            Format::Json => format!(
+                "{{\"day\":{},\"part\":{},\"answer\":{},\"elapsed_ns\":{},\"input\":{}}}",
+                day,
+                answer.part,
+                json_string(&answer.value),
+                answer.elapsed.as_nanos(),
+                json_string(&fingerprint.to_string())
+            ),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Answer, Part};
+    use std::time::Duration;
+
+    #[test]
+    fn test_json_lines() {
+        let mut args = vec!["10".to_string(), "--json".to_string()];
+        assert_eq!(Format::from_args(&mut args), Format::Json);
+        assert_eq!(args, ["10"]);
+        assert_eq!(Format::from_args(&mut args), Format::Text);
+
+        let run = Run {
+            parse_time: Duration::ZERO,
+            answers: vec![Answer {
+                part: Part::Two,
+                value: "a \"b\"\n".to_string(),
+                elapsed: Duration::from_micros(3),
+            }],
+        };
+        assert_eq!(
+            lines(13, &run, fingerprint(""), Format::Json),
+            [
+                r#"{"day":13,"part":2,"answer":"a \"b\"\n","elapsed_ns":3000,"input":"cbf29ce484222325"}"#
+            ]
+        );
+        assert_eq!(
+            lines(13, &run, fingerprint(""), Format::Text),
+            ["Part 2: a \"b\"\n"]
+        );
+        assert_ne!(fingerprint("1"), fingerprint("2"));
+        assert_eq!(fingerprint("1\n2"), fingerprint("1  \n\n2\n"));
+    }
+}
@@ -0,0 +1,262 @@
+use crate::InputError;
+use std::fmt::{self, Display, Formatter};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// A parse failure at a 1-based line and column of the input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for InputError {
+    fn from(err: ParseError) -> InputError {
+        InputError::Malformed {
+            message: err.to_string(),
+        }
+    }
+}
+
+/// A piece of the input that remembers where it came from, so errors can point at it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Field<'a> {
+    pub text: &'a str,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl<'a> Field<'a> {
+    pub fn new(line: usize, text: &'a str) -> Field<'a> {
+        Field {
+            text,
+            line,
+            column: 1,
+        }
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    fn slice(&self, start: usize, end: usize) -> Field<'a> {
+        Field {
+            text: &self.text[start..end],
+            line: self.line,
+            column: self.column + self.text[..start].chars().count(),
+        }
+    }
+
+    pub fn trim(&self) -> Field<'a> {
+        let start = self.text.len() - self.text.trim_start().len();
+        let end = self.text.trim_end().len().max(start);
+        self.slice(start, end)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    pub fn split(&self, delimiter: &str) -> Vec<Field<'a>> {
+        let mut fields = vec![];
+        let mut start = 0;
+        for (idx, _) in self.text.match_indices(delimiter) {
+            fields.push(self.slice(start, idx));
+            start = idx + delimiter.len();
+        }
+        fields.push(self.slice(start, self.text.len()));
+        fields
+    }
+
+    /// Splits on runs of whitespace, dropping the whitespace.
+    pub fn words(&self) -> Vec<Field<'a>> {
+        self.text
+            .split_whitespace()
+            .map(|word| {
+                let start = word.as_ptr() as usize - self.text.as_ptr() as usize;
+                self.slice(start, start + word.len())
+            })
+            .collect()
+    }
+
+    /// Splits on `delimiter` and requires exactly `count` fields.
+    pub fn record(&self, delimiter: &str, count: usize) -> Result<Vec<Field<'a>>, ParseError> {
+        let fields = self.split(delimiter);
+        if fields.len() != count {
+            return Err(self.error(format!(
+                "expected {} fields separated by {:?}, found {}",
+                count,
+                delimiter,
+                fields.len()
+            )));
+        }
+        Ok(fields)
+    }
+
+    pub fn parse<T: FromStr>(&self) -> Result<T, ParseError> {
+        self.text.trim().parse().map_err(|_| {
+            self.trim()
+                .error(format!("could not parse {:?}", self.text.trim()))
+        })
+    }
+
+    /// Parses a list of integers such as `3,4,3,1,2`.
+    pub fn integers<T: FromStr>(&self, delimiter: &str) -> Result<Vec<T>, ParseError> {
+        self.split(delimiter)
+            .iter()
+            .filter(|it| !it.is_empty())
+            .map(|it| it.parse())
+            .collect()
+    }
+
+    /// Parses `key=lo..hi`, returning the key and the inclusive range.
+    pub fn range<T: FromStr>(&self) -> Result<(&'a str, RangeInclusive<T>), ParseError> {
+        let key_value = self.trim().record("=", 2)?;
+        let bounds = key_value[1].record("..", 2)?;
+        Ok((
+            key_value[0].trim().text,
+            bounds[0].parse()?..=bounds[1].parse()?,
+        ))
+    }
+}
+
+pub fn lines(input: &str) -> Vec<Field<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| Field::new(idx + 1, line))
+        .collect()
+}
+
+/// The lines of a settings file with the words on each, skipping blank lines and lines starting
+/// with `#`.
+pub fn config_lines(input: &str) -> Vec<(Field<'_>, Vec<Field<'_>>)> {
+    lines(input)
+        .iter()
+        .map(|it| it.trim())
+        .filter(|it| !it.is_empty() && !it.text.starts_with('#'))
+        .map(|it| (it, it.words()))
+        .collect()
+}
+
+/// Groups lines into the blank-line-separated sections of the input.
+pub fn sections(input: &str) -> Vec<Vec<Field<'_>>> {
+    let mut sections = vec![];
+    let mut current = vec![];
+    for line in lines(input) {
+        if line.is_empty() {
+            if !current.is_empty() {
+                sections.push(current);
+                current = vec![];
+            }
+        } else {
+            current.push(line.trim());
+        }
+    }
+    if !current.is_empty() {
+        sections.push(current);
+    }
+    sections
+}
+
+/// Parses a rectangular block of characters, mapping each one with `cell`.
+pub fn grid<T>(input: &str, cell: impl Fn(char) -> Option<T>) -> Result<Vec<Vec<T>>, ParseError> {
+    let mut rows: Vec<Vec<T>> = vec![];
+    for line in lines(input).iter().map(|it| it.trim()) {
+        if line.is_empty() {
+            continue;
+        }
+        let mut row = vec![];
+        for (idx, c) in line.text.chars().enumerate() {
+            row.push(cell(c).ok_or_else(|| ParseError {
+                line: line.line,
+                column: line.column + idx,
+                message: format!("unexpected character {:?}", c),
+            })?);
+        }
+        if let Some(first) = rows.first() {
+            if first.len() != row.len() {
+                return Err(line.error(format!(
+                    "expected {} columns, found {}",
+                    first.len(),
+                    row.len()
+                )));
+            }
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_columns() {
+        let line = Field::new(3, "  ab -> c");
+        let fields = line.trim().record(" -> ", 2).unwrap();
+        assert_eq!(fields[1].text, "c");
+        assert_eq!(fields[1].column, 9);
+        let err = line.record("-", 3).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        let err = Field::new(1, "x 12 y").split(" ")[2]
+            .parse::<i32>()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 6: could not parse \"y\"");
+    }
+
+    #[test]
+    fn test_sections_and_lists() {
+        let input = "NNCB\n\nCH -> B\nHH -> N\n\n\n3,4,-3\n";
+        let sections = sections(input);
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[1][1].line, 4);
+        assert_eq!(sections[2][0].integers::<i32>(",").unwrap(), vec![3, 4, -3]);
+    }
+
+    #[test]
+    fn test_config_lines() {
+        let config = config_lines("# pairs\n\n  ( )\t3  1\n\t# tab\nx");
+        assert_eq!(config.len(), 2);
+        let (line, words) = &config[0];
+        assert_eq!((line.line, line.column), (3, 3));
+        let words: Vec<_> = words.iter().map(|it| (it.text, it.column)).collect();
+        assert_eq!(words, [("(", 3), (")", 5), ("3", 7), ("1", 10)]);
+        assert_eq!(config[1].1[0].line, 5);
+    }
+
+    #[test]
+    fn test_range() {
+        let fields = Field::new(1, "target area: x=20..30, y=-10..-5").split(", ");
+        let (key, range) = fields[1].range::<i32>().unwrap();
+        assert_eq!((key, range), ("y", -10..=-5));
+        assert!(Field::new(1, "x=20").range::<i32>().is_err());
+    }
+
+    #[test]
+    fn test_grid() {
+        let digit = |c: char| c.to_digit(10);
+        assert_eq!(grid("12\n34", digit).unwrap(), vec![vec![1, 2], vec![3, 4]]);
+        let err = grid("12\n3x", digit).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert!(grid("12\n345", digit).is_err());
+    }
+}
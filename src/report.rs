@@ -0,0 +1,159 @@
+use crate::bench::format_duration;
+use crate::output::{self, Fingerprint};
+use crate::{Part, Run, Solver};
+use rayon::prelude::*;
+use std::any::Any;
+use std::fmt::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Status {
+    Solved(Run),
+    Failed(String),
+    Panicked(String),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DayReport {
+    pub day: u16,
+    pub status: Status,
+    pub elapsed: Duration,
+    pub fingerprint: Option<Fingerprint>,
+}
+
+impl DayReport {
+    pub fn is_ok(&self) -> bool {
+        matches!(self.status, Status::Solved(_))
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Loads and solves one day, turning errors and panics into a status instead of stopping.
+pub fn run_day(solver: &dyn Solver, part: Option<Part>) -> DayReport {
+    let start = Instant::now();
+    let mut fingerprint = None;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let input = solver.load()?;
+        fingerprint = Some(output::fingerprint(&input));
+        solver.solve(&input, part)
+    }));
+    let status = match result {
+        Ok(Ok(run)) => Status::Solved(run),
+        Ok(Err(err)) => Status::Failed(err.to_string()),
+        Err(payload) => Status::Panicked(panic_message(payload)),
+    };
+    DayReport {
+        day: solver.day(),
+        status,
+        elapsed: start.elapsed(),
+        fingerprint,
+    }
+}
+
+/// Runs every solver concurrently on the rayon pool, returning reports in the solvers' order.
+pub fn run_all(solvers: &[&dyn Solver], part: Option<Part>) -> Vec<DayReport> {
+    solvers
+        .par_iter()
+        .map(|solver| run_day(*solver, part))
+        .collect()
+}
+
+pub fn summary(reports: &[DayReport], wall_time: Duration) -> String {
+    let mut out = String::new();
+    for report in reports {
+        let elapsed = format_duration(report.elapsed);
+        match &report.status {
+            Status::Solved(run) => {
+                writeln!(out, "Day {}: ok in {}", report.day, elapsed).unwrap();
+                for answer in &run.answers {
+                    writeln!(
+                        out,
+                        "  Part {}: {} ({})",
+                        answer.part,
+                        answer.value,
+                        format_duration(answer.elapsed)
+                    )
+                    .unwrap();
+                }
+            }
+            Status::Failed(err) => {
+                writeln!(out, "Day {}: failed after {}: {}", report.day, elapsed, err).unwrap()
+            }
+            Status::Panicked(message) => writeln!(
+                out,
+                "Day {}: panicked after {}: {}",
+                report.day, elapsed, message
+            )
+            .unwrap(),
+        }
+    }
+    let failed = reports.iter().filter(|it| !it.is_ok()).count();
+    let cpu_time: Duration = reports.iter().map(|it| it.elapsed).sum();
+    writeln!(
+        out,
+        "{} day(s) solved, {} failed, in {} ({} summed)",
+        reports.len() - failed,
+        failed,
+        format_duration(wall_time),
+        format_duration(cpu_time)
+    )
+    .unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InputError, Solution};
+
+    struct Flaky;
+
+    impl Solution for Flaky {
+        const DAY: u16 = 99;
+        type Input = u32;
+        type Answer1 = u32;
+        type Answer2 = u32;
+
+        fn load(&self) -> Result<String, InputError> {
+            Ok("7".to_string())
+        }
+
+        fn parse(&self, input: &str) -> Result<u32, InputError> {
+            input.parse().map_err(|_| InputError::Malformed {
+                message: "not a number".to_string(),
+            })
+        }
+
+        fn part1(&self, input: &u32) -> u32 {
+            input * 2
+        }
+
+        fn part2(&self, _input: &u32) -> u32 {
+            panic!("part 2 is not done")
+        }
+    }
+
+    #[test]
+    fn test_run_all() {
+        let reports = run_all(&[&Flaky, &Flaky], Some(Part::One));
+        assert!(reports.iter().all(|it| it.is_ok()));
+        let reports = run_all(&[&Flaky], None);
+        assert_eq!(
+            reports[0].status,
+            Status::Panicked("part 2 is not done".to_string())
+        );
+        let summary = summary(&reports, Duration::ZERO);
+        assert!(summary.contains("Day 99: panicked after"));
+        assert!(summary.contains("0 day(s) solved, 1 failed, in 0.000µs"));
+    }
+}
@@ -0,0 +1,250 @@
+use crate::event;
+use crate::trace::Level;
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A node in a search graph. States are identified by `key`, so two states with the same key are
+/// treated as the same node however they were reached.
+pub trait SearchState: Sized {
+    type Key: Hash + Eq;
+    type Move: Clone;
+
+    fn key(&self) -> Self::Key;
+    /// The moves that can be made from this state, with the state each leads to and its cost.
+    fn successors(&self) -> Vec<(Self::Move, Self, u64)>;
+    fn is_goal(&self) -> bool;
+}
+
+#[derive(Clone, Debug)]
+pub struct Path<S: SearchState> {
+    pub moves: Vec<S::Move>,
+    pub cost: u64,
+    pub goal: S,
+    /// How many states were taken off the frontier before the goal was reached.
+    pub expanded: usize,
+}
+
+struct Visited<M> {
+    cost: u64,
+    parent: Option<(usize, M)>,
+}
+
+// Records a route to `key` costing `cost`, returning its id if it is new or cheaper than before.
+fn visit<K: Hash + Eq, M>(
+    ids: &mut HashMap<K, usize>,
+    visited: &mut Vec<Visited<M>>,
+    key: K,
+    cost: u64,
+    parent: Option<(usize, M)>,
+) -> Option<usize> {
+    match ids.entry(key) {
+        Entry::Occupied(entry) => {
+            let id = *entry.get();
+            if cost >= visited[id].cost {
+                return None;
+            }
+            visited[id] = Visited { cost, parent };
+            Some(id)
+        }
+        Entry::Vacant(entry) => {
+            entry.insert(visited.len());
+            visited.push(Visited { cost, parent });
+            Some(visited.len() - 1)
+        }
+    }
+}
+
+fn reconstruct<S: SearchState>(
+    visited: &mut [Visited<S::Move>],
+    mut id: usize,
+    goal: S,
+    expanded: usize,
+) -> Path<S> {
+    let cost = visited[id].cost;
+    let mut moves = vec![];
+    while let Some((parent, mv)) = visited[id].parent.take() {
+        moves.push(mv);
+        id = parent;
+    }
+    moves.reverse();
+    Path {
+        moves,
+        cost,
+        goal,
+        expanded,
+    }
+}
+
+struct Frontier<S> {
+    estimate: u64,
+    cost: u64,
+    id: usize,
+    state: S,
+}
+
+// Ordered so that `BinaryHeap` pops the lowest estimate first, preferring deeper paths on ties.
+impl<S> Ord for Frontier<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then(self.cost.cmp(&other.cost))
+    }
+}
+
+impl<S> PartialOrd for Frontier<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> PartialEq for Frontier<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S> Eq for Frontier<S> {}
+
+/// The cheapest path from `start` to a goal. `heuristic` must never overestimate the remaining
+/// cost, or the path found may not be the cheapest.
+pub fn astar<S: SearchState>(start: S, heuristic: impl Fn(&S) -> u64) -> Option<Path<S>> {
+    let mut ids = HashMap::new();
+    let mut visited = vec![];
+    let mut frontier = BinaryHeap::new();
+    let mut expanded = 0;
+    let id = visit(&mut ids, &mut visited, start.key(), 0, None)?;
+    frontier.push(Frontier {
+        estimate: heuristic(&start),
+        cost: 0,
+        id,
+        state: start,
+    });
+    while let Some(Frontier {
+        cost, id, state, ..
+    }) = frontier.pop()
+    {
+        if cost > visited[id].cost {
+            continue;
+        }
+        expanded += 1;
+        event!(
+            Level::Trace,
+            "expand",
+            cost = cost,
+            expanded = expanded,
+            frontier = frontier.len()
+        );
+        if state.is_goal() {
+            event!(Level::Debug, "goal", cost = cost, expanded = expanded);
+            return Some(reconstruct(&mut visited, id, state, expanded));
+        }
+        for (mv, next, step) in state.successors() {
+            let next_cost = cost + step;
+            let parent = Some((id, mv));
+            if let Some(next_id) = visit(&mut ids, &mut visited, next.key(), next_cost, parent) {
+                frontier.push(Frontier {
+                    estimate: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    id: next_id,
+                    state: next,
+                });
+            }
+        }
+    }
+    None
+}
+
+pub fn dijkstra<S: SearchState>(start: S) -> Option<Path<S>> {
+    astar(start, |_| 0)
+}
+
+/// The path to a goal with the fewest moves, ignoring move costs except to total them.
+pub fn bfs<S: SearchState>(start: S) -> Option<Path<S>> {
+    let mut ids = HashMap::new();
+    let mut visited = vec![];
+    let mut queue = VecDeque::new();
+    let mut expanded = 0;
+    let id = visit(&mut ids, &mut visited, start.key(), 0, None)?;
+    queue.push_back((id, start));
+    while let Some((id, state)) = queue.pop_front() {
+        expanded += 1;
+        event!(
+            Level::Trace,
+            "expand",
+            cost = visited[id].cost,
+            expanded = expanded,
+            frontier = queue.len()
+        );
+        if state.is_goal() {
+            event!(
+                Level::Debug,
+                "goal",
+                cost = visited[id].cost,
+                expanded = expanded
+            );
+            return Some(reconstruct(&mut visited, id, state, expanded));
+        }
+        for (mv, next, step) in state.successors() {
+            let key = next.key();
+            if ids.contains_key(&key) {
+                continue;
+            }
+            let cost = visited[id].cost + step;
+            let next_id = visit(&mut ids, &mut visited, key, cost, Some((id, mv)))?;
+            queue.push_back((next_id, next));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Walking a number line towards 10, one step for 2 or a jump of 4 for 10.
+    #[derive(Clone, Copy, Debug)]
+    struct Walk(i32);
+
+    impl SearchState for Walk {
+        type Key = i32;
+        type Move = char;
+
+        fn key(&self) -> i32 {
+            self.0
+        }
+
+        fn successors(&self) -> Vec<(char, Walk, u64)> {
+            vec![
+                ('+', Walk(self.0 + 1), 2),
+                ('-', Walk(self.0 - 1), 2),
+                ('J', Walk(self.0 + 4), 10),
+            ]
+            .into_iter()
+            .filter(|(_, it, _)| (-5..=20).contains(&it.0))
+            .collect()
+        }
+
+        fn is_goal(&self) -> bool {
+            self.0 == 10
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let cheapest = dijkstra(Walk(0)).unwrap();
+        assert_eq!(cheapest.cost, 20);
+        assert_eq!(cheapest.moves, ['+'; 10]);
+        assert_eq!(cheapest.goal.0, 10);
+
+        let guided = astar(Walk(0), |it| (10 - it.0).unsigned_abs() as u64 * 2).unwrap();
+        assert_eq!(guided.cost, 20);
+        assert!(guided.expanded <= cheapest.expanded);
+
+        let shortest = bfs(Walk(0)).unwrap();
+        assert_eq!(shortest.moves.len(), 4);
+        assert_eq!(shortest.cost, 24);
+    }
+}
@@ -0,0 +1,186 @@
+use std::cell::RefCell;
+use std::fmt::{self, Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How much solvers report while they run. `Debug` gives an event per result worth seeing, such
+/// as a probe hitting the target; `Trace` adds one per step, such as each search expansion.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Level {
+    #[default]
+    Off,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn from_u8(level: u8) -> Level {
+        match level {
+            0 => Level::Off,
+            1 => Level::Debug,
+            _ => Level::Trace,
+        }
+    }
+
+    /// Removes `-v`, `-vv` and `--verbose` from `args`, returning the verbosity they ask for.
+    pub fn from_args(args: &mut Vec<String>) -> Level {
+        let mut level = Level::Off;
+        args.retain(|it| {
+            let wanted = match it.as_str() {
+                "-v" | "--verbose" => Level::Debug,
+                "-vv" => Level::Trace,
+                _ => return true,
+            };
+            level = level.max(wanted);
+            false
+        });
+        level
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Level::Off => write!(f, "OFF"),
+            Level::Debug => write!(f, "DEBUG"),
+            Level::Trace => write!(f, "TRACE"),
+        }
+    }
+}
+
+// The level in the low byte, and above it the number of threads capturing, whose own levels
+// take precedence.
+static STATE: AtomicUsize = AtomicUsize::new(0);
+const CAPTURING: usize = 1 << 8;
+
+thread_local! {
+    static CAPTURED: RefCell<Option<(Level, Vec<String>)>> = const { RefCell::new(None) };
+}
+
+pub fn set_level(level: Level) {
+    let _ = STATE.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |state| {
+        Some(state & !(CAPTURING - 1) | level as usize)
+    });
+}
+
+/// Whether events at `level` are wanted. Checked before building an event, so disabled tracing
+/// costs one load while no thread is capturing.
+pub fn enabled(level: Level) -> bool {
+    let state = STATE.load(Ordering::Relaxed);
+    let global = Level::from_u8(state as u8);
+    if state < CAPTURING {
+        return level <= global;
+    }
+    let captured = CAPTURED.with(|it| it.borrow().as_ref().map(|(level, _)| *level));
+    level <= captured.unwrap_or(global)
+}
+
+/// One thing a solver did: where it came from, what happened and named values describing it.
+pub struct Event<'a> {
+    pub level: Level,
+    pub target: &'a str,
+    pub name: &'a str,
+    pub fields: &'a [(&'a str, &'a dyn Display)],
+}
+
+// Written as `LEVEL target name key=value ...`, quoting values that contain spaces or `=`.
+impl Display for Event<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        let target = self.target.split("::").last().unwrap_or(self.target);
+        write!(f, "{} {} {}", self.level, target, self.name)?;
+        for (key, value) in self.fields {
+            let value = value.to_string();
+            if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '=') {
+                write!(f, " {}={:?}", key, value)?;
+            } else {
+                write!(f, " {}={}", key, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Prints an event to stderr, or records it if the current thread is capturing.
+pub fn emit(event: &Event) {
+    let line = event.to_string();
+    let line = CAPTURED.with(|it| match it.borrow_mut().as_mut() {
+        Some((_, events)) => {
+            events.push(line);
+            None
+        }
+        None => Some(line),
+    });
+    if let Some(line) = line {
+        eprintln!("{}", line);
+    }
+}
+
+// Ends a capture when dropped, so a panic in the captured code still restores the outer one.
+struct Capturing {
+    outer: Option<(Level, Vec<String>)>,
+}
+
+impl Drop for Capturing {
+    fn drop(&mut self) {
+        STATE.fetch_sub(CAPTURING, Ordering::Relaxed);
+        CAPTURED.with(|it| it.replace(self.outer.take()));
+    }
+}
+
+/// Runs `f` with events up to `level` from this thread collected instead of printed.
+pub fn capture(level: Level, f: impl FnOnce()) -> Vec<String> {
+    let outer = CAPTURED.with(|it| it.replace(Some((level, vec![]))));
+    STATE.fetch_add(CAPTURING, Ordering::Relaxed);
+    let _capturing = Capturing { outer };
+    f();
+    CAPTURED
+        .with(|it| it.borrow_mut().take())
+        .map_or_else(Vec::new, |(_, events)| events)
+}
+
+/// Emits an event if `level` is enabled, e.g. `event!(Level::Debug, "hit", x = 1, y = 2)`.
+#[macro_export]
+macro_rules! event {
+    ($level:expr, $name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled($level) {
+            $crate::trace::emit(&$crate::trace::Event {
+                level: $level,
+                target: module_path!(),
+                name: $name,
+                fields: &[$((stringify!($key), &$value as &dyn std::fmt::Display)),*],
+            });
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture() {
+        let mut args = vec!["-v".to_string(), "17".to_string(), "-vv".to_string()];
+        assert_eq!(Level::from_args(&mut args), Level::Trace);
+        assert_eq!(args, ["17"]);
+
+        let events = capture(Level::Debug, || {
+            event!(Level::Debug, "hit", x = 6, note = "two words");
+            event!(Level::Trace, "step", x = 1);
+        });
+        assert_eq!(events, ["DEBUG tests hit x=6 note=\"two words\""]);
+        assert!(!enabled(Level::Debug));
+    }
+
+    #[test]
+    fn test_capture_panics() {
+        let events = capture(Level::Debug, || {
+            let panicked = std::panic::catch_unwind(|| {
+                capture(Level::Trace, || panic!("inner"));
+            });
+            assert!(panicked.is_err());
+            assert!(!enabled(Level::Trace));
+            event!(Level::Debug, "after");
+        });
+        assert_eq!(events, ["DEBUG tests after"]);
+        assert!(CAPTURED.with(|it| it.borrow().is_none()));
+    }
+}